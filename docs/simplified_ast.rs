@@ -1,5 +1,6 @@
 pub enum TypeName {
   IntType,
+  RealType,
   StringType,
   BoolType,
 }
@@ -7,6 +8,8 @@ pub enum TypeName {
 pub enum LiteralValue {
   StringLiteral(String),
   IntLiteral(i32),
+  RealLiteral(f64),
+  BoolLiteral(bool),
 }
 
 pub enum BinaryOperator {
@@ -14,13 +17,21 @@ pub enum BinaryOperator {
   Sub,
   Mul,
   Div,
+  // Remainder of truncated division, which has the sign of the dividend.
+  Rem,
   LessThan,
+  LessThanOrEqual,
+  GreaterThan,
+  GreaterThanOrEqual,
   Equal,
+  NotEqual,
   And,
+  Or,
 }
 
 pub enum UnaryOperator {
   Not,
+  Negate,
 }
 
 pub enum Expression {
@@ -36,20 +47,28 @@ pub enum Expression {
 pub enum Statement {
   Declare {
     name: String,
-    type_of: TypeName,
     // Option<...> means the value is optional.
+    type_of: Option<TypeName>,
     initial: Option<Expression>,
+    is_constant: bool,
+    doc: Option<String>,
   },
   Assign(String, Expression),
   For {
     variable: String,
     from: Expression,
     to: Expression,
+    step: Option<Expression>,
+    is_descending: bool,
     // Vec<...> is a contiguous list.
     run: Vec<Statement>,
   },
-  Print(Expression),
-  Read(String),
+  Print {
+    // Values and their optional widths.
+    arguments: Vec<(Expression, Option<Expression>)>,
+    newline: bool,
+  },
+  Read(Vec<String>),
   Assert(Expression),
 }
 
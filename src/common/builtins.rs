@@ -0,0 +1,154 @@
+// Built-in functions, which are shared by the type checker and the interpreter.
+use std::collections::HashMap;
+
+use common::errors::RuntimeError;
+use common::types::TypeName::*;
+use common::types::Value::*;
+use common::types::{TypeName, Value};
+
+/// Native implementation of a built-in function. The arguments have already been type checked.
+pub type BuiltinFn = fn(&[Value]) -> Result<Value, RuntimeError>;
+
+pub struct Builtin {
+  pub parameters: Vec<TypeName>,
+  pub return_type: TypeName,
+  pub implementation: BuiltinFn,
+}
+
+/// A registry of built-in functions by name.
+pub struct Builtins {
+  functions: HashMap<&'static str, Builtin>,
+}
+
+impl Builtins {
+  pub fn get(&self, name: &str) -> Option<&Builtin> {
+    self.functions.get(name)
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.functions.contains_key(name)
+  }
+
+  fn register(
+    &mut self,
+    name: &'static str,
+    parameters: Vec<TypeName>,
+    return_type: TypeName,
+    implementation: BuiltinFn,
+  ) {
+    let builtin = Builtin {
+      parameters,
+      return_type,
+      implementation,
+    };
+    self.functions.insert(name, builtin);
+  }
+}
+
+impl Default for Builtins {
+  /// Creates a registry containing the standard library.
+  fn default() -> Builtins {
+    let mut builtins = Builtins {
+      functions: HashMap::new(),
+    };
+
+    builtins.register("abs", vec![IntType], IntType, abs);
+    builtins.register("min", vec![IntType, IntType], IntType, min);
+    builtins.register("max", vec![IntType, IntType], IntType, max);
+    builtins.register("pow", vec![IntType, IntType], IntType, pow);
+    builtins.register("length", vec![StringType], IntType, length);
+    builtins.register("to_string", vec![IntType], StringType, to_string);
+    builtins.register("parse_int", vec![StringType], IntType, parse_int);
+
+    builtins
+  }
+}
+
+fn abs(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [IntV(x)] => x.checked_abs().map(IntV).ok_or(RuntimeError::IntegerOverflow),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+fn min(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [IntV(a), IntV(b)] => Ok(IntV(a.min(b))),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+fn max(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [IntV(a), IntV(b)] => Ok(IntV(a.max(b))),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+fn pow(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [IntV(_), IntV(exponent)] if exponent < 0 => Err(RuntimeError::NegativeExponent(exponent)),
+    [IntV(base), IntV(exponent)] => base
+      .checked_pow(exponent as u32)
+      .map(IntV)
+      .ok_or(RuntimeError::IntegerOverflow),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+// The number of characters, not bytes.
+fn length(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [StringV(ref s)] => Ok(IntV(s.chars().count() as i32)),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+fn to_string(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [IntV(x)] => Ok(StringV(x.to_string())),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+fn parse_int(arguments: &[Value]) -> Result<Value, RuntimeError> {
+  match *arguments {
+    [StringV(ref s)] => str::parse(s.trim())
+      .map(IntV)
+      .map_err(|_| RuntimeError::InvalidNumber(s.to_string())),
+    _ => panic!("Type checker will prevent this."),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn call(name: &str, arguments: &[Value]) -> Result<Value, RuntimeError> {
+    let builtins = Builtins::default();
+    let builtin = builtins.get(name).expect("Builtin should exist.");
+    (builtin.implementation)(arguments)
+  }
+
+  #[test]
+  fn signatures_match_implementations() {
+    assert_eq!(call("pow", &[IntV(2), IntV(10)]), Ok(IntV(1024)));
+    assert_eq!(call("min", &[IntV(2), IntV(-3)]), Ok(IntV(-3)));
+    assert_eq!(call("to_string", &[IntV(-12)]), Ok(StringV("-12".to_string())));
+  }
+
+  #[test]
+  fn overflow_is_an_error() {
+    assert_eq!(call("abs", &[IntV(i32::MIN)]), Err(RuntimeError::IntegerOverflow));
+    assert_eq!(call("pow", &[IntV(2), IntV(31)]), Err(RuntimeError::IntegerOverflow));
+  }
+
+  #[test]
+  fn parse_int_rejects_invalid_numbers() {
+    assert_eq!(call("parse_int", &[StringV(" 42 ".to_string())]), Ok(IntV(42)));
+    assert_eq!(
+      call("parse_int", &[StringV("4x".to_string())]),
+      Err(RuntimeError::InvalidNumber("4x".to_string()))
+    );
+  }
+}
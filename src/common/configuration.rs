@@ -6,10 +6,18 @@ pub enum LogLevel {
   Debug,
 }
 
+/// Determines how declarations shadowing a variable from an outer scope are reported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShadowingPolicy {
+  Warn,
+  Error,
+}
+
 #[derive(Debug)]
 pub struct Options {
   pub log_level: LogLevel,
   pub input_file: String,
+  pub shadowing: ShadowingPolicy,
 }
 
 impl Default for Options {
@@ -17,6 +25,7 @@ impl Default for Options {
     Options {
       log_level: LogLevel::Normal,
       input_file: "./minipl/hello.pl".to_string(),
+      shadowing: ShadowingPolicy::Warn,
     }
   }
 }
@@ -36,6 +45,14 @@ where
         let file_name = args.pop_front().expect("Expected file name after --file.");
         options.input_file = file_name;
       }
+      "--shadowing" => {
+        let policy = args.pop_front().expect("Expected warn or error after --shadowing.");
+        options.shadowing = match policy.as_str() {
+          "warn" => ShadowingPolicy::Warn,
+          "error" => ShadowingPolicy::Error,
+          other => panic!("Unknown shadowing policy: {}", other),
+        };
+      }
       otherwise => println!("WARNING: Unknown command line argument: {}", otherwise),
     }
   }
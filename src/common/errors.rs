@@ -1,4 +1,5 @@
 use std::io::Error;
+use std::ops::Range;
 
 use parsing::token::*;
 
@@ -54,6 +55,12 @@ pub enum LexerError {
   UnknownEscapeCode(String),
   UnterminatedStringLiteral,
   InvalidNumberLiteral,
+  // The excess over i32::MAX, or below i32::MIN for a negated literal, if it fits in a u128.
+  IntegerLiteralOverflow {
+    literal: String,
+    is_negated: bool,
+    excess: Option<u128>,
+  },
   UnterminatedComment,
   CharStreamError(CharStreamError),
   IOError(String),
@@ -76,6 +83,23 @@ impl ErrorWithReason for LexerError {
       )),
       UnterminatedStringLiteral => Some("Unterminated string literal.".to_string()),
       InvalidNumberLiteral => Some("Invalid number literal.".to_string()),
+      IntegerLiteralOverflow {
+        ref literal,
+        is_negated,
+        excess,
+      } => {
+        let (sign, limit) = if is_negated {
+          ("-", format!("the smallest int is {}", i32::MIN))
+        } else {
+          ("", format!("the largest int is {}", i32::MAX))
+        };
+        let amount = excess.map_or(String::new(), |excess| format!(" by {}", excess));
+
+        Some(format!(
+          "Integer literal {}{} overflows int{} ({}).",
+          sign, literal, amount, limit
+        ))
+      }
       UnterminatedComment => Some("Unterminated comment literal.".to_string()),
       CharStreamError(ref error) => Some(format!(
         "Character stream error: {}",
@@ -94,6 +118,12 @@ pub enum ParserError {
   LexerError(LexerError),
   MissingRParen,
   IncompleteExpression,
+  InvalidArrayLength,
+  UnreadableImport { path: String, reason: String },
+  CyclicImport(String),
+  // Imported files may only contain declarations.
+  StatementInImport,
+  InvalidCaseLabel,
 }
 
 pub type ParserErrorWithCtx = ErrWithCtx<ParserError>;
@@ -110,6 +140,20 @@ impl ErrorWithReason for ParserError {
         "Unbalanced parenthesis in expression (probably missing right parenthesis?)".to_string(),
       ),
       ParserError::IncompleteExpression => Some("Incomplete expression.".to_string()),
+      ParserError::InvalidArrayLength => {
+        Some("Array length must be a positive integer literal.".to_string())
+      }
+      ParserError::UnreadableImport {
+        ref path,
+        ref reason,
+      } => Some(format!("Can't import {}: {}", path, reason)),
+      ParserError::StatementInImport => {
+        Some("Imported files may only contain declarations.".to_string())
+      }
+      ParserError::InvalidCaseLabel => Some("Case labels must be literals.".to_string()),
+      ParserError::CyclicImport(ref path) => {
+        Some(format!("Import of {} forms a cycle.", path))
+      }
       ParserError::UnexpectedToken { expected, was } => Some(format!(
         "Unexpected token. Expected {:?}, was {:?}",
         expected, was
@@ -122,6 +166,70 @@ impl ErrorWithReason for ParserError {
   }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeError {
+  IndexOutOfBounds { index: i32, length: usize },
+  InvalidSlice { start: i32, end: i32, length: usize },
+  DivisionByZero,
+  IntegerOverflow,
+  NegativeExponent(i32),
+  InvalidNumber(String),
+  InvalidBool(String),
+  ZeroStep,
+  StackOverflow(usize),
+  InvalidWidth { width: i32, max: i32 },
+}
+
+impl ErrorWithReason for RuntimeError {
+  fn get_reason(&self) -> Option<String> {
+    match *self {
+      RuntimeError::IndexOutOfBounds { index, length } => Some(format!(
+        "Index {} is out of bounds for length {}.",
+        index, length
+      )),
+      RuntimeError::InvalidSlice { start, end, length } => Some(format!(
+        "Slice {}..{} is out of bounds for length {}.",
+        start, end, length
+      )),
+      RuntimeError::DivisionByZero => Some("Division by zero.".to_string()),
+      RuntimeError::IntegerOverflow => Some("Integer overflow.".to_string()),
+      RuntimeError::NegativeExponent(exponent) => {
+        Some(format!("Exponent must not be negative, was {}.", exponent))
+      }
+      RuntimeError::InvalidNumber(ref text) => Some(format!("\"{}\" is not a valid number.", text)),
+      RuntimeError::ZeroStep => Some("The step of a for loop must not be zero.".to_string()),
+      RuntimeError::InvalidBool(ref text) => Some(format!(
+        "\"{}\" is not a valid bool (expected true or false).",
+        text
+      )),
+      RuntimeError::StackOverflow(depth) => Some(format!(
+        "Stack overflow: calls may be nested at most {} deep.",
+        depth
+      )),
+      RuntimeError::InvalidWidth { width, max } => Some(format!(
+        "Print width {} is out of range, the largest width is {}.",
+        width, max
+      )),
+    }
+  }
+}
+
+/// A runtime error, with the source position of the statement which caused it.
+#[derive(Debug)]
+pub struct RuntimeErrorWithCtx(pub RuntimeError, pub Range<usize>);
+
+impl ErrorWithContext for RuntimeErrorWithCtx {
+  fn get_offset(&self) -> usize {
+    self.1.start
+  }
+}
+
+impl ErrorWithReason for RuntimeErrorWithCtx {
+  fn get_reason(&self) -> Option<String> {
+    self.0.get_reason()
+  }
+}
+
 pub trait AddCtxToError
 where
   Self: ErrorWithReason + Sized,
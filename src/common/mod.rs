@@ -1,3 +1,4 @@
+pub mod builtins;
 pub mod configuration;
 pub mod errors;
 #[macro_use]
@@ -1,33 +1,86 @@
+use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TypeName {
   IntType,
+  RealType,
   StringType,
   BoolType,
+  // Element type and length.
+  ArrayType(Box<TypeName>, usize),
+  // A user-defined type, which is looked up from `UserTypes`.
+  NamedType(String),
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum UserType {
+  // Field names and types, in declaration order.
+  Record(Vec<(String, TypeName)>),
+  // Member names in declaration order.
+  Enum(Vec<String>),
+}
+
+/// User-defined types by name.
+pub type UserTypes = HashMap<String, UserType>;
+
 #[derive(Debug, PartialEq, Clone)]
 /// These values exist at parse time.
 pub enum LiteralValue {
   StringLiteral(String),
   IntLiteral(i32),
+  RealLiteral(f64),
+  BoolLiteral(bool),
 }
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 /// These values exist at runtime.
 pub enum Value {
   IntV(i32),
+  RealV(f64),
   StringV(String),
   BoolV(bool),
+  ArrayV(Vec<Value>),
+  // Type name, and field names and values in declaration order.
+  RecordV(String, Vec<(String, Value)>),
+  // Type name, and the index and name of the member. Members are ordered by their index.
+  EnumV(String, usize, String),
 }
 
 impl TypeName {
-  pub fn get_default_value(self) -> Value {
-    match self {
+  pub fn get_default_value(&self, user_types: &UserTypes) -> Value {
+    match *self {
       TypeName::IntType => Value::IntV(0),
+      TypeName::RealType => Value::RealV(0.0),
       TypeName::StringType => Value::StringV("".to_string()),
       TypeName::BoolType => Value::BoolV(false),
+      TypeName::ArrayType(ref element, length) => {
+        Value::ArrayV(vec![element.get_default_value(user_types); length])
+      }
+      TypeName::NamedType(ref name) => match user_types[name] {
+        UserType::Record(ref fields) => Value::RecordV(
+          name.to_string(),
+          fields
+            .iter()
+            .map(|(field, type_of)| (field.to_string(), type_of.get_default_value(user_types)))
+            .collect(),
+        ),
+        // Enums default to their first member.
+        UserType::Enum(ref members) => Value::EnumV(name.to_string(), 0, members[0].to_string()),
+      },
+    }
+  }
+}
+
+impl fmt::Display for TypeName {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      TypeName::IntType => write!(f, "int"),
+      TypeName::RealType => write!(f, "real"),
+      TypeName::StringType => write!(f, "string"),
+      TypeName::BoolType => write!(f, "bool"),
+      TypeName::ArrayType(ref element, length) => write!(f, "array[{}] of {}", length, element),
+      TypeName::NamedType(ref name) => write!(f, "{}", name),
     }
   }
 }
@@ -36,8 +89,16 @@ impl Value {
   pub fn get_type(&self) -> TypeName {
     match *self {
       Value::IntV(_) => TypeName::IntType,
+      Value::RealV(_) => TypeName::RealType,
       Value::StringV(_) => TypeName::StringType,
       Value::BoolV(_) => TypeName::BoolType,
+      // Arrays always have at least one element.
+      Value::ArrayV(ref elements) => {
+        TypeName::ArrayType(Box::new(elements[0].get_type()), elements.len())
+      }
+      Value::RecordV(ref name, _) | Value::EnumV(ref name, ..) => {
+        TypeName::NamedType(name.to_string())
+      }
     }
   }
 }
@@ -47,7 +108,9 @@ impl From<LiteralValue> for Value {
   fn from(literal: LiteralValue) -> Value {
     match literal {
       LiteralValue::IntLiteral(i) => Value::IntV(i),
+      LiteralValue::RealLiteral(r) => Value::RealV(r),
       LiteralValue::StringLiteral(s) => Value::StringV(s),
+      LiteralValue::BoolLiteral(b) => Value::BoolV(b),
     }
   }
 }
@@ -57,7 +120,28 @@ impl fmt::Display for Value {
     match *self {
       Value::StringV(ref s) => write!(f, "{}", s),
       Value::IntV(i) => write!(f, "{}", i),
+      // The debug representation is the shortest one which parses back to the same value, and
+      // it always includes a decimal point or an exponent.
+      Value::RealV(r) => write!(f, "{:?}", r),
       Value::BoolV(b) => write!(f, "{}", if b { "true" } else { "false" }),
+      Value::ArrayV(ref elements) => {
+        write!(f, "[")?;
+        for (i, element) in elements.iter().enumerate() {
+          if i > 0 {
+            write!(f, ", ")?;
+          }
+          write!(f, "{}", element)?;
+        }
+        write!(f, "]")
+      }
+      Value::RecordV(ref name, ref fields) => {
+        write!(f, "{} {{", name)?;
+        for (i, (field, value)) in fields.iter().enumerate() {
+          write!(f, "{} {}: {}", if i > 0 { "," } else { "" }, field, value)?;
+        }
+        write!(f, " }}")
+      }
+      Value::EnumV(_, _, ref member) => write!(f, "{}", member),
     }
   }
 }
@@ -68,14 +152,55 @@ pub enum BinaryOperator {
   Sub,
   Mul,
   Div,
+  // Remainder of truncated division, which has the sign of the dividend.
+  Rem,
   LessThan,
+  LessThanOrEqual,
+  GreaterThan,
+  GreaterThanOrEqual,
   Equal,
+  NotEqual,
   And,
+  Or,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum UnaryOperator {
   Not,
+  Negate,
+}
+
+// Operators are displayed as they are written in source code.
+impl fmt::Display for BinaryOperator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    use self::BinaryOperator::*;
+
+    let symbol = match *self {
+      Add => "+",
+      Sub => "-",
+      Mul => "*",
+      Div => "/",
+      Rem => "%",
+      LessThan => "<",
+      LessThanOrEqual => "<=",
+      GreaterThan => ">",
+      GreaterThanOrEqual => ">=",
+      Equal => "=",
+      NotEqual => "<>",
+      And => "&",
+      Or => "|",
+    };
+    write!(f, "{}", symbol)
+  }
+}
+
+impl fmt::Display for UnaryOperator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      UnaryOperator::Not => write!(f, "!"),
+      UnaryOperator::Negate => write!(f, "-"),
+    }
+  }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -97,11 +222,11 @@ impl Operator {
     use self::UnaryOperator::*;
 
     match self {
-      UnaryOperator(Not) => 3,
+      UnaryOperator(Not) | UnaryOperator(Negate) => 3,
       BinaryOperator(op) => match op {
-        Mul | Div => 2,
-        Add | Sub | And => 1,
-        LessThan | Equal => 0,
+        Mul | Div | Rem => 2,
+        Add | Sub | And | Or => 1,
+        LessThan | LessThanOrEqual | GreaterThan | GreaterThanOrEqual | Equal | NotEqual => 0,
       },
     }
   }
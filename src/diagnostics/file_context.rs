@@ -3,39 +3,100 @@ use std::rc::Rc;
 
 #[derive(Debug, Clone)]
 pub struct FilePosition {
+  pub file_id: usize,
   pub offset: usize,
   pub column: usize,
   pub row: usize,
 }
 
-pub struct FileContextSource {
+pub struct SourceFile {
   pub file_name: Option<String>,
-  // Values are tuples of (offset from start, content)
+  // The offset of the first character. Each file occupies its own range of offsets, so that an
+  // offset alone identifies the file it points into.
+  pub base_offset: usize,
+  pub length: usize,
+  // Values are tuples of (offset from start of the source map, content)
   // Line data is stored using reference counted pointers so that
   // we can safely give out references to them.
   pub lines: Vec<(usize, Rc<String>)>,
 }
 
+impl SourceFile {
+  fn contains(&self, offset: usize) -> bool {
+    // The end of file is a valid offset, e.g. for unexpected end of input.
+    offset >= self.base_offset && offset <= self.base_offset + self.length
+  }
+}
+
+/// A source map of all the files which make up a program.
+#[derive(Default)]
+pub struct FileContextSource {
+  pub files: Vec<SourceFile>,
+}
+
 impl FileContextSource {
+  pub fn new() -> FileContextSource {
+    FileContextSource { files: Vec::new() }
+  }
+
+  /// Creates a source map containing a single file.
   pub fn from_str(source: &str, file_name: Option<String>) -> FileContextSource {
+    let mut context = FileContextSource::new();
+    context.add_file(source, file_name);
+    context
+  }
+
+  /// Adds a file to the source map, and returns its file id.
+  pub fn add_file(&mut self, source: &str, file_name: Option<String>) -> usize {
+    // Leave a gap after the previous file, so that its end of file offset stays unique.
+    let base_offset = self
+      .files
+      .last()
+      .map_or(0, |file| file.base_offset + file.length + 1);
+
     let lines = source
       .lines()
-      .scan(0, |offs, line| {
+      .scan(base_offset, |offs, line| {
         let initial_offs = *offs;
-        *offs = *offs + line.len() + 1;
+        // Offsets count characters, not bytes.
+        *offs = *offs + line.chars().count() + 1;
         Some((initial_offs, line.to_string().into()))
       })
       .collect();
 
-    FileContextSource { lines, file_name }
+    self.files.push(SourceFile {
+      file_name,
+      base_offset,
+      length: source.chars().count(),
+      lines,
+    });
+
+    self.files.len() - 1
+  }
+
+  pub fn base_offset(&self, file_id: usize) -> usize {
+    self.files[file_id].base_offset
+  }
+
+  pub fn file_name(&self, file_id: usize) -> Option<&str> {
+    self.files[file_id].file_name.as_deref()
+  }
+
+  /// Returns the id of the file containing the given offset.
+  pub fn file_id(&self, offset: usize) -> Option<usize> {
+    self.files.iter().position(|file| file.contains(offset))
   }
 
   pub fn decode_offset(&self, offset: usize) -> Option<FilePosition> {
-    for (row_index, &(first_index, ref row_content)) in self.lines.iter().enumerate() {
-      let len = row_content.len();
+    let file_id = self.file_id(offset)?;
+    let file = &self.files[file_id];
+
+    for (row_index, &(first_index, ref row_content)) in file.lines.iter().enumerate() {
+      let len = row_content.chars().count();
 
       if offset >= first_index && offset <= first_index + len {
         return Some(FilePosition {
+          file_id,
           offset,
           row: row_index + 1,
           column: offset - first_index + 1,
@@ -43,17 +104,29 @@ impl FileContextSource {
       }
     }
 
-    None
+    // The end of a file which ends in a newline is on the (empty) row after the last line.
+    let first_index = file
+      .lines
+      .last()
+      .map_or(file.base_offset, |(offs, content)| offs + content.chars().count() + 1);
+
+    Some(FilePosition {
+      file_id,
+      offset,
+      row: file.lines.len() + 1,
+      column: offset - first_index + 1,
+    })
   }
 
   // Terminology:
   // A row is a 1-based index into the file
   // A line is a string, containing the contents of a particular row.
-  pub fn get_line(&self, row: usize) -> Option<Rc<String>> {
-    self
+  pub fn get_line(&self, file_id: usize, row: usize) -> Option<Rc<String>> {
+    let index = row.checked_sub(1)?;
+    self.files[file_id]
       .lines
-      .get(row - 1)
-      .map(|&(_, ref content_pointer)| content_pointer.clone())
+      .get(index)
+      .map(|(_, content_pointer)| content_pointer.clone())
   }
 
   pub fn get_range_lines(&self, range: &Range<usize>) -> Vec<Rc<String>> {
@@ -65,8 +138,9 @@ impl FileContextSource {
       .decode_offset(range.end)
       .expect("This should be a valid offset.");
 
+    // The row after the last line has no content.
     (start.row..end.row + 1)
-      .map(|x| self.get_line(x).expect("Should be a valid row."))
+      .filter_map(|x| self.get_line(start.file_id, x))
       .collect()
   }
 
@@ -93,3 +167,46 @@ impl FileContextSource {
     Self::format_source_quote(pos.row, &source_lines)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn offsets_are_unique_across_files() {
+    let mut context = FileContextSource::new();
+    let first = context.add_file("var x : int;\nprint x;", Some("a.pl".to_string()));
+    let second = context.add_file("print 1;", Some("b.pl".to_string()));
+
+    let base = context.base_offset(second);
+    assert!(base > 21);
+
+    let position = context.decode_offset(base + 6).expect("Should be a valid offset.");
+    assert_eq!((position.file_id, position.row, position.column), (second, 1, 7));
+    assert_eq!(context.file_name(position.file_id), Some("b.pl"));
+
+    let position = context.decode_offset(19).expect("Should be a valid offset.");
+    assert_eq!((position.file_id, position.row, position.column), (first, 2, 7));
+  }
+
+  #[test]
+  fn rows_before_the_first_line_do_not_exist() {
+    let context = FileContextSource::from_str("print 1;", None);
+    assert!(context.get_line(0, 0).is_none());
+    assert_eq!(*context.get_line(0, 1).unwrap(), "print 1;");
+  }
+
+  #[test]
+  fn offsets_count_characters() {
+    let context = FileContextSource::from_str("var äiti := 1;\nprint äiti;", None);
+    let position = context.decode_offset(21).expect("Should be a valid offset.");
+    assert_eq!((position.row, position.column), (2, 7));
+  }
+
+  #[test]
+  fn end_of_file_after_a_newline() {
+    let context = FileContextSource::from_str("print\n", None);
+    let position = context.decode_offset(6).expect("Should be a valid offset.");
+    assert_eq!((position.row, position.column), (2, 1));
+  }
+}
@@ -1,7 +1,8 @@
-#![feature(slice_patterns)]
 #![cfg_attr(feature = "clippy", feature(plugin))]
 #![cfg_attr(feature = "clippy", plugin(clippy))]
 
+extern crate unicode_xid;
+
 use std::rc::Rc;
 
 #[macro_use]
@@ -11,20 +12,20 @@ pub mod parsing;
 pub mod runtime;
 pub mod semantic;
 
+use common::configuration::ShadowingPolicy;
 use common::errors::*;
 use common::logger::Logger;
 use common::util::ResultExt;
 use diagnostics::file_context::*;
-use parsing::char_stream::CharStream;
-use parsing::lexer::BufferedLexer;
-use parsing::parser::Parser;
+use parsing::loader::Loader;
 use runtime::*;
 use semantic::type_checker::*;
 
 #[derive(Debug)]
 pub enum ExecutionError {
   ParserError(ParserErrorWithCtx),
-  TypeError(TypeError),
+  TypeError(TypeErrorWithCtx),
+  RuntimeError(RuntimeErrorWithCtx),
 }
 
 impl From<ParserErrorWithCtx> for ExecutionError {
@@ -33,36 +34,35 @@ impl From<ParserErrorWithCtx> for ExecutionError {
   }
 }
 
-impl From<TypeError> for ExecutionError {
-  fn from(err: TypeError) -> ExecutionError {
+impl From<RuntimeErrorWithCtx> for ExecutionError {
+  fn from(err: RuntimeErrorWithCtx) -> ExecutionError {
+    ExecutionError::RuntimeError(err)
+  }
+}
+
+impl From<TypeErrorWithCtx> for ExecutionError {
+  fn from(err: TypeErrorWithCtx) -> ExecutionError {
     ExecutionError::TypeError(err)
   }
 }
 
 /// Run a script using the given IO handler (e.g `ConsoleIo`).
+/// The script and every file it imports are added to `file_context`, which can be used to decode
+/// the offsets of the returned errors.
 pub fn run_script<T: Io>(
   source: &str,
+  file_name: Option<String>,
   io: &mut T,
-  logger: Rc<Logger>,
-  file_context: Option<Rc<FileContextSource>>,
+  logger: Rc<dyn Logger>,
+  file_context: &mut FileContextSource,
+  shadowing: ShadowingPolicy,
 ) -> Result<(), Vec<ExecutionError>> {
-  // If we don't have a file context source, construct a new one.
-  let file_context =
-    file_context.unwrap_or_else(|| Rc::new(FileContextSource::from_str(source, None)));
-
   // This is our compiler pipeline:
 
-  // We'll wrap the source string into a stream-like type for easier use and O(1) indexing.
-  let tokens = CharStream::new(source);
-
-  // The lexer splits the stream into tokens, and buffers them to allow peeking and backtracking.
-  let lexer = BufferedLexer::new(tokens, logger.clone());
+  // The loader lexes and parses the script and its imports, and merges them into one program AST.
+  let mut loader = Loader::new(file_context, logger.clone());
 
-  // The parser parses the token stream into an AST.
-  let mut parser = Parser::new(lexer, logger.clone());
-
-  // ... which we'll use to obtain the program AST.
-  let program = parser.parse_program().map_err(|errors| {
+  let mut program = loader.load(source, file_name).map_err(|errors| {
     errors
       .into_iter()
       .map(ExecutionError::ParserError)
@@ -70,13 +70,16 @@ pub fn run_script<T: Io>(
   })?;
 
   // Run the type checker.
-  type_check(&program)
+  type_check(&mut program, shadowing, logger.clone())
     .map_err(ExecutionError::TypeError)
     .vec_err()?;
 
   // If type checking was succesful, create a new interpreter and run the program.
-  let mut interpreter = Interpreter::new(io, &file_context);
-  interpreter.execute(&program);
+  let mut interpreter = Interpreter::new(io, file_context);
+  interpreter
+    .execute(&program)
+    .map_err(ExecutionError::RuntimeError)
+    .vec_err()?;
 
   Ok(())
 }
@@ -1,6 +1,7 @@
 extern crate miniplrs;
 
 use std::env;
+use std::fmt::Debug;
 use std::io::Read;
 use std::path::Path;
 use std::rc::Rc;
@@ -8,7 +9,7 @@ use std::rc::Rc;
 use miniplrs::common::configuration::parse_command_line_args;
 use miniplrs::common::errors::{ErrorWithContext, ErrorWithReason};
 use miniplrs::common::logger::ConsoleLogger;
-use miniplrs::diagnostics::file_context::FileContextSource;
+use miniplrs::diagnostics::file_context::{FileContextSource, FilePosition};
 use miniplrs::runtime::console_io::ConsoleIo;
 use miniplrs::{run_script, ExecutionError};
 
@@ -30,18 +31,18 @@ fn main() {
 
   let logger = ConsoleLogger::from_options(&options);
 
-  let file_context = Rc::new(FileContextSource::from_str(
-    &source,
-    Some(options.input_file),
-  ));
+  // Holds the source of the script and the files it imports, for quoting them in error messages.
+  let mut file_context = FileContextSource::new();
 
   match run_script(
     &source,
+    Some(options.input_file),
     &mut io,
     Rc::new(logger),
-    Some(file_context.clone()),
+    &mut file_context,
+    options.shadowing,
   ) {
-    Ok(_) => return,
+    Ok(_) => {}
     Err(errors) => {
       // println!("Errors: {:?}", errors);
       print_errors(&errors, &file_context);
@@ -49,18 +50,29 @@ fn main() {
   }
 }
 
-fn print_errors(errors: &[ExecutionError], ctx: &FileContextSource) {
-  let file_info_part = if let Some(ref file_name) = ctx.file_name {
-    format!(" in {}", file_name)
+fn format_position(position: &FilePosition, ctx: &FileContextSource) -> String {
+  let file_info_part = if let Some(file_name) = ctx.file_name(position.file_id) {
+    format!("In {}, on", file_name)
   } else {
-    "".to_string()
+    "On".to_string()
   };
 
+  format!(
+    "{} row {}, column {}:",
+    file_info_part, position.row, position.column
+  )
+}
+
+// Every error should have a reason, but a missing one mustn't crash the error report.
+fn reason<E: ErrorWithReason + Debug>(err: &E) -> String {
+  err.get_reason().unwrap_or_else(|| format!("{:?}", err))
+}
+
+fn print_errors(errors: &[ExecutionError], ctx: &FileContextSource) {
   println!(
-    "Encountered {} {}{}:\n",
+    "Encountered {} {}:\n",
     errors.len(),
-    if errors.len() == 1 { "error" } else { "errors" },
-    file_info_part
+    if errors.len() == 1 { "error" } else { "errors" }
   );
 
   for error in errors {
@@ -71,17 +83,30 @@ fn print_errors(errors: &[ExecutionError], ctx: &FileContextSource) {
         let quoted_line_range = position.row - 1..position.row + 2;
 
         let quoted_lines = quoted_line_range
-          .map(|i| (i, ctx.get_line(i)))
+          .map(|i| (i, ctx.get_line(position.file_id, i)))
           .filter_map(|(i, line)| line.map(|x| FileContextSource::format_source_quote_line(i, &x)))
           .fold(String::new(), |acc, x| acc + &x);
 
-        println!("On row {}, column {}:", position.row, position.column);
-        println!("Parser error: {}", err.get_reason().unwrap());
+        println!("{}", format_position(&position, ctx));
+        println!("Parser error: {}", reason(err));
 
         println!("{}", quoted_lines);
       }
       ExecutionError::TypeError(err) => {
-        println!("Type error: {}", err.get_reason().unwrap());
+        let position = ctx.decode_offset(err.get_offset()).unwrap();
+
+        println!("{}", format_position(&position, ctx));
+        println!("Type error: {}", reason(err));
+
+        println!("{}", ctx.get_source_quote(&err.1));
+      }
+      ExecutionError::RuntimeError(err) => {
+        let position = ctx.decode_offset(err.get_offset()).unwrap();
+
+        println!("{}", format_position(&position, ctx));
+        println!("Runtime error: {}", reason(err));
+
+        println!("{}", ctx.get_source_quote(&err.1));
       }
     }
   }
@@ -6,29 +6,143 @@ use common::types::*;
 pub enum Expression {
   Literal(LiteralValue),
   Variable(String),
+  Call(String, Vec<Expression>),
+  // Array or string, and index.
+  Index(Box<(Expression, Expression)>),
+  // String, and the start and end of the slice. Both are inclusive.
+  Slice(Box<(Expression, Expression, Expression)>),
+  // Record and field name.
+  Field(Box<Expression>, String),
+  // Explicit conversion to a primitive type, e.g `real(x)`.
+  Convert(TypeName, Box<Expression>),
+  // A string literal with embedded expressions, e.g `"x is ${x}"`.
+  Interpolation(Vec<InterpolationPart>),
   // We have to heap allocate the sub expressions, because otherwise this type
   // wouldn't have a fixed (maximum) size.
   BinaryOp(BinaryOperator, Box<(Expression, Expression)>),
   UnaryOp(UnaryOperator, Box<Expression>),
 }
 
+#[derive(Debug)]
+pub enum InterpolationPart {
+  Text(String),
+  Value(Expression),
+}
+
 #[derive(Debug)]
 pub enum Statement {
   Declare {
     name: String,
-    type_of: TypeName,
+    // If the type is omitted, it is inferred from the initial value by the type checker.
+    type_of: Option<TypeName>,
+    // Constants and variables without a type always have an initial value.
     initial: Option<Expression>,
+    is_constant: bool,
+    // The "///" comment before the declaration, so that tools can show it.
+    doc: Option<String>,
   },
-  Assign(String, Expression),
+  Assign(LValue, Expression),
   For {
     variable: String,
     from: Expression,
     to: Expression,
+    // Defaults to 1. A negative step counts down.
+    step: Option<Expression>,
+    // `downto` counts down by the magnitude of the step.
+    is_descending: bool,
     run: Vec<StatementWithCtx>,
   },
-  Print(Expression),
-  Read(String),
+  If {
+    condition: Expression,
+    then_branch: Vec<StatementWithCtx>,
+    // Empty if the statement has no else branch.
+    else_branch: Vec<StatementWithCtx>,
+  },
+  Case {
+    scrutinee: Expression,
+    arms: Vec<CaseArm>,
+    // Executed when no label matches.
+    default: Vec<StatementWithCtx>,
+  },
+  While {
+    condition: Expression,
+    run: Vec<StatementWithCtx>,
+  },
+  Break,
+  Continue,
+  Print {
+    arguments: Vec<PrintArgument>,
+    // println ends the output with a newline.
+    newline: bool,
+  },
+  // Variables are read in order from whitespace-separated input.
+  Read(Vec<String>),
   Assert(Expression),
+  Function(Function),
+  TypeDefinition {
+    name: String,
+    definition: UserType,
+  },
+  Call(String, Vec<Expression>),
+  Return(Option<Expression>),
+}
+
+impl Statement {
+  /// Returns true for the statements which an imported file may contain.
+  pub fn is_declaration(&self) -> bool {
+    matches!(
+      *self,
+      Statement::Declare { .. } | Statement::Function(_) | Statement::TypeDefinition { .. }
+    )
+  }
+}
+
+/// An assignable location.
+#[derive(Debug)]
+pub enum LValue {
+  Variable(String),
+  Index(Box<LValue>, Expression),
+  Field(Box<LValue>, String),
+}
+
+impl LValue {
+  /// Returns the name of the variable containing the location.
+  pub fn get_name(&self) -> &str {
+    match *self {
+      LValue::Variable(ref name) => name,
+      LValue::Index(ref inner, _) | LValue::Field(ref inner, _) => inner.get_name(),
+    }
+  }
+}
+
+/// A value to print, optionally padded with spaces to a minimum width (e.g `x:5`). Values are
+/// aligned to the right, or to the left if the width is negative.
+#[derive(Debug)]
+pub struct PrintArgument {
+  pub value: Expression,
+  pub width: Option<Expression>,
+}
+
+/// The statements executed when the scrutinee of a case statement equals one of the labels.
+#[derive(Debug)]
+pub struct CaseArm {
+  pub labels: Vec<LiteralValue>,
+  pub body: Vec<StatementWithCtx>,
+}
+
+#[derive(Debug)]
+pub struct Parameter {
+  pub name: String,
+  pub type_of: TypeName,
+}
+
+/// A procedure or a function. Procedures don't have a return type.
+#[derive(Debug)]
+pub struct Function {
+  pub name: String,
+  pub parameters: Vec<Parameter>,
+  pub return_type: Option<TypeName>,
+  pub body: Vec<StatementWithCtx>,
 }
 
 #[derive(Debug)]
@@ -37,4 +151,12 @@ pub struct StatementWithCtx {
   pub statement: Statement,
 }
 
+/// An `import "path";` declaration. Imports are resolved by the loader before type checking, so
+/// they are not part of the statement list.
+#[derive(Debug, PartialEq)]
+pub struct Import {
+  pub path: String,
+  pub source_position: Range<usize>,
+}
+
 pub type Program<'a> = &'a [StatementWithCtx];
@@ -22,18 +22,42 @@ pub fn div(a: Expression, b: Expression) -> Expression {
   BinaryOp(Div, Box::new((a, b)))
 }
 
+pub fn rem(a: Expression, b: Expression) -> Expression {
+  BinaryOp(Rem, Box::new((a, b)))
+}
+
 pub fn eq(a: Expression, b: Expression) -> Expression {
   BinaryOp(Equal, Box::new((a, b)))
 }
 
+pub fn neq(a: Expression, b: Expression) -> Expression {
+  BinaryOp(NotEqual, Box::new((a, b)))
+}
+
 pub fn lt(a: Expression, b: Expression) -> Expression {
   BinaryOp(LessThan, Box::new((a, b)))
 }
 
+pub fn lte(a: Expression, b: Expression) -> Expression {
+  BinaryOp(LessThanOrEqual, Box::new((a, b)))
+}
+
+pub fn gt(a: Expression, b: Expression) -> Expression {
+  BinaryOp(GreaterThan, Box::new((a, b)))
+}
+
+pub fn gte(a: Expression, b: Expression) -> Expression {
+  BinaryOp(GreaterThanOrEqual, Box::new((a, b)))
+}
+
 pub fn and(a: Expression, b: Expression) -> Expression {
   BinaryOp(And, Box::new((a, b)))
 }
 
+pub fn or(a: Expression, b: Expression) -> Expression {
+  BinaryOp(Or, Box::new((a, b)))
+}
+
 pub fn int(i: i32) -> Expression {
   Literal(IntLiteral(i))
 }
@@ -43,13 +67,21 @@ pub fn string(s: &str) -> Expression {
 }
 
 pub fn print(expr: Expression) -> Statement {
-  Print(expr)
+  Print {
+    arguments: vec![PrintArgument {
+      value: expr,
+      width: None,
+    }],
+    newline: false,
+  }
 }
 
 pub fn declare(variable: &str, type_of: TypeName, initial: Option<Expression>) -> Statement {
   Declare {
     name: variable.to_string(),
-    type_of,
+    type_of: Some(type_of),
     initial,
+    is_constant: false,
+    doc: None,
   }
 }
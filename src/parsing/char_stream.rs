@@ -6,31 +6,54 @@ use common::errors::*;
 #[derive(Debug)]
 pub struct CharStream {
   chars: Vec<char>,
-  pub offset: usize,
+  position: usize,
+  // Added to the position of every character, so that offsets are unique across source files.
+  base_offset: usize,
 }
 
 impl CharStream {
   /// Creates a new char stream from a char slice.
   pub fn new(src: &str) -> CharStream {
+    CharStream::with_base_offset(src, 0)
+  }
+
+  /// Creates a new char stream whose offsets start from `base_offset`.
+  pub fn with_base_offset(src: &str, base_offset: usize) -> CharStream {
     CharStream {
       chars: src.chars().collect(),
-      offset: 0,
+      position: 0,
+      base_offset,
     }
   }
 
+  /// Returns the offset of the current character.
+  pub fn offset(&self) -> usize {
+    self.base_offset + self.position
+  }
+
   pub fn advance(&mut self) {
-    self.offset = min(self.chars.len(), self.offset + 1);
+    self.position = min(self.chars.len(), self.position + 1);
   }
 
   pub fn peek(&self) -> Result<char, CharStreamError> {
     if self.reached_end() {
       Err(CharStreamError::EndOfFile)
     } else {
-      let ch = self.chars[self.offset];
+      let ch = self.chars[self.position];
       Ok(ch)
     }
   }
 
+  /// Returns the character `distance` characters after the current one, without advancing.
+  pub fn peek_at(&self, distance: usize) -> Result<char, CharStreamError> {
+    self
+      .chars
+      .get(self.position + distance)
+      .cloned()
+      .ok_or(CharStreamError::EndOfFile)
+  }
+
+  #[allow(clippy::should_implement_trait)]
   pub fn next(&mut self) -> Result<char, CharStreamError> {
     let ch = self.peek()?;
     self.advance();
@@ -38,11 +61,11 @@ impl CharStream {
   }
 
   pub fn reached_end(&self) -> bool {
-    self.offset >= self.chars.len()
+    self.position >= self.chars.len()
   }
 
-  fn slice_at(&self, offset: usize, length: usize) -> &[char] {
-    &self.chars[offset..offset + length]
+  fn slice_at(&self, position: usize, length: usize) -> &[char] {
+    &self.chars[position..position + length]
   }
 
   pub fn advance_until<F>(&mut self, pred: F)
@@ -61,8 +84,8 @@ impl CharStream {
   where
     F: Fn(char) -> bool,
   {
-    let offs = self.offset;
+    let start = self.position;
     self.advance_until(pred);
-    self.slice_at(offs, self.offset - offs)
+    self.slice_at(start, self.position - start)
   }
 }
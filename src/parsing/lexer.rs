@@ -9,9 +9,13 @@ use parsing::token::*;
 use parsing::token_stream::TokenStream;
 use parsing::util::*;
 
-fn read_string_literal(input: &mut CharStream) -> Result<Token, LexerError> {
+fn read_string_literal(
+  input: &mut CharStream,
+  logger: Rc<dyn Logger>,
+) -> Result<Token, LexerError> {
   input.advance();
 
+  let mut parts = Vec::new();
   let mut chars = Vec::new();
 
   loop {
@@ -27,17 +31,67 @@ fn read_string_literal(input: &mut CharStream) -> Result<Token, LexerError> {
           'r' => '\r',
           'n' => '\n',
           't' => '\t',
+          '$' => '$',
           other => return Err(LexerError::UnknownEscapeCode(other.to_string())),
         };
         chars.push(escape_char);
       }
+      '$' if input.peek().ok() == Some('{') => {
+        input.advance();
+
+        if !chars.is_empty() {
+          parts.push(StringPart::Text(chars.drain(..).collect()));
+        }
+
+        parts.push(StringPart::Code(read_interpolated_tokens(input, logger.clone())?));
+      }
       '"' => break,
       other => chars.push(other),
     };
   }
 
   let contents = chars.iter().collect();
-  Ok(Token::Literal(LiteralValue::StringLiteral(contents)))
+
+  if parts.is_empty() {
+    return Ok(Token::Literal(LiteralValue::StringLiteral(contents)));
+  }
+
+  if !chars.is_empty() {
+    parts.push(StringPart::Text(contents));
+  }
+
+  Ok(Token::InterpolatedString(parts))
+}
+
+// Reads the tokens of an expression embedded in a string literal, up to and including the closing
+// brace. The brace is replaced with EndOfFile, so that the tokens can be parsed on their own.
+fn read_interpolated_tokens(
+  input: &mut CharStream,
+  logger: Rc<dyn Logger>,
+) -> Result<Vec<TokenWithCtx>, LexerError> {
+  let mut tokens = Vec::new();
+
+  loop {
+    skip_trivia(input).map_err(|err| err.0)?;
+
+    if let Ok('}') = input.peek() {
+      tokens.push(TokenWithCtx {
+        offset: input.offset(),
+        token: Token::EndOfFile,
+      });
+      input.advance();
+      return Ok(tokens);
+    }
+
+    // Errors inside the interpolation are reported at the start of the string literal.
+    let token = next_token(input, logger.clone()).map_err(|err| err.0)?;
+
+    if token.token == Token::EndOfFile {
+      return Err(LexerError::UnterminatedStringLiteral);
+    }
+
+    tokens.push(token);
+  }
 }
 
 fn read_keyword_or_identifier(input: &mut CharStream) -> Result<Token, LexerError> {
@@ -46,16 +100,38 @@ fn read_keyword_or_identifier(input: &mut CharStream) -> Result<Token, LexerErro
   // have to compare using slice patterns.
   match *chars {
     ['p', 'r', 'i', 'n', 't'] => Ok(Token::Print),
+    ['p', 'r', 'i', 'n', 't', 'l', 'n'] => Ok(Token::PrintLn),
     ['r', 'e', 'a', 'd'] => Ok(Token::Read),
     ['i', 'n', 't'] => Ok(Token::Type(TypeName::IntType)),
+    ['r', 'e', 'a', 'l'] => Ok(Token::Type(TypeName::RealType)),
     ['b', 'o', 'o', 'l'] => Ok(Token::Type(TypeName::BoolType)),
     ['s', 't', 'r', 'i', 'n', 'g'] => Ok(Token::Type(TypeName::StringType)),
     ['v', 'a', 'r'] => Ok(Token::Var),
+    ['c', 'o', 'n', 's', 't'] => Ok(Token::Const),
     ['a', 's', 's', 'e', 'r', 't'] => Ok(Token::Assert),
     ['f', 'o', 'r'] => Ok(Token::For),
     ['i', 'n'] => Ok(Token::In),
     ['d', 'o'] => Ok(Token::Do),
+    ['d', 'o', 'w', 'n', 't', 'o'] => Ok(Token::DownTo),
+    ['s', 't', 'e', 'p'] => Ok(Token::Step),
     ['e', 'n', 'd'] => Ok(Token::End),
+    ['i', 'f'] => Ok(Token::If),
+    ['t', 'h', 'e', 'n'] => Ok(Token::Then),
+    ['c', 'a', 's', 'e'] => Ok(Token::Case),
+    ['e', 'l', 's', 'e'] => Ok(Token::Else),
+    ['w', 'h', 'i', 'l', 'e'] => Ok(Token::While),
+    ['b', 'r', 'e', 'a', 'k'] => Ok(Token::Break),
+    ['c', 'o', 'n', 't', 'i', 'n', 'u', 'e'] => Ok(Token::Continue),
+    ['p', 'r', 'o', 'c', 'e', 'd', 'u', 'r', 'e'] => Ok(Token::Procedure),
+    ['f', 'u', 'n', 'c', 't', 'i', 'o', 'n'] => Ok(Token::Function),
+    ['r', 'e', 't', 'u', 'r', 'n'] => Ok(Token::Return),
+    ['a', 'r', 'r', 'a', 'y'] => Ok(Token::Array),
+    ['o', 'f'] => Ok(Token::Of),
+    ['t', 'y', 'p', 'e'] => Ok(Token::TypeDef),
+    ['i', 'm', 'p', 'o', 'r', 't'] => Ok(Token::Import),
+    ['r', 'e', 'c', 'o', 'r', 'd'] => Ok(Token::Record),
+    ['t', 'r', 'u', 'e'] => Ok(Token::Literal(LiteralValue::BoolLiteral(true))),
+    ['f', 'a', 'l', 's', 'e'] => Ok(Token::Literal(LiteralValue::BoolLiteral(false))),
     _ => {
       let name: String = chars.iter().collect();
 
@@ -77,58 +153,251 @@ fn parse_single_char_token(ch: char) -> Token {
 
   match ch {
     ';' => Semicolon,
+    ',' => Comma,
     '(' => LParen,
     ')' => RParen,
+    '[' => LBracket,
+    ']' => RBracket,
     '+' => Operator(BinaryOperator(Add)),
     '-' => Operator(BinaryOperator(Sub)),
     '*' => Operator(BinaryOperator(Mul)),
     '/' => Operator(BinaryOperator(Div)),
+    '%' => Operator(BinaryOperator(Rem)),
     '<' => Operator(BinaryOperator(LessThan)),
+    '>' => Operator(BinaryOperator(GreaterThan)),
     '=' => Operator(BinaryOperator(Equal)),
     '&' => Operator(BinaryOperator(And)),
+    '|' => Operator(BinaryOperator(Or)),
     '!' => Operator(UnaryOperator(Not)),
     _ => panic!("This should not happen."),
   }
 }
 
+fn is_number_at(input: &CharStream, distance: usize) -> bool {
+  input.peek_at(distance).map(is_number).unwrap_or(false)
+}
+
+// Returns the radix of a "0x", "0o" or "0b" prefix at the current position.
+fn read_radix_prefix(input: &CharStream) -> Option<u32> {
+  if input.peek().ok() != Some('0') {
+    return None;
+  }
+
+  match input.peek_at(1) {
+    Ok('x') | Ok('X') => Some(16),
+    Ok('o') | Ok('O') => Some(8),
+    Ok('b') | Ok('B') => Some(2),
+    _ => None,
+  }
+}
+
+// Reads a run of digits in the given radix, as written. Underscores may separate digits, but
+// they can't lead, trail or follow each other.
+fn read_digits(input: &mut CharStream, radix: u32) -> Result<String, LexerError> {
+  let chars = input.take_until(|ch| !ch.is_digit(radix) && ch != '_');
+
+  let misplaced_separator = chars.first() == Some(&'_')
+    || chars.last() == Some(&'_')
+    || chars.windows(2).any(|pair| pair == ['_', '_']);
+
+  if chars.is_empty() || misplaced_separator {
+    return Err(LexerError::InvalidNumberLiteral);
+  }
+
+  Ok(chars.iter().collect())
+}
+
+fn without_separators(literal: &str) -> String {
+  literal.chars().filter(|&ch| ch != '_').collect()
+}
+
+// The literal is kept as written for error messages. Its digits follow a prefix of the given
+// length.
+fn parse_int_literal(
+  literal: String,
+  prefix_length: usize,
+  radix: u32,
+) -> Result<Token, LexerError> {
+  let digits = without_separators(&literal[prefix_length..]);
+  let max = u128::from(i32::MAX.unsigned_abs());
+
+  // Every digit has been validated, so parsing only fails if the value doesn't fit in a u128.
+  match u128::from_str_radix(&digits, radix) {
+    Ok(i) if i <= max => Ok(Token::Literal(LiteralValue::IntLiteral(i as i32))),
+    Ok(i) if i == max + 1 => Ok(Token::MinIntMagnitude(literal)),
+    Ok(i) => Err(LexerError::IntegerLiteralOverflow {
+      literal,
+      is_negated: false,
+      excess: Some(i - max),
+    }),
+    Err(_) => Err(LexerError::IntegerLiteralOverflow {
+      literal,
+      is_negated: false,
+      excess: None,
+    }),
+  }
+}
+
+// Number literals are always non-negative, but the magnitude of i32::MIN doesn't fit in an i32.
+// That literal gets its own token, and the parser only accepts it after a prefix minus.
 fn read_number_literal(input: &mut CharStream) -> Result<Token, LexerError> {
-  let digits = input.take_until(|ch| !is_number(ch));
-  let digits_as_str: String = digits.iter().collect();
+  if let Some(radix) = read_radix_prefix(input) {
+    let mut literal = String::new();
+    literal.push(input.next()?);
+    literal.push(input.next()?);
+    literal.push_str(&read_digits(input, radix)?);
+
+    return parse_int_literal(literal, 2, radix);
+  }
+
+  let mut literal = read_digits(input, 10)?;
+  let mut is_real = false;
+
+  // A dot is only a decimal point if it's followed by a digit, because "1..2" is a range.
+  if let Ok('.') = input.peek() {
+    if is_number_at(input, 1) {
+      literal.push(input.next()?);
+      literal.push_str(&read_digits(input, 10)?);
+      is_real = true;
+    }
+  }
+
+  // Likewise, an exponent must have digits.
+  if let Ok('e') | Ok('E') = input.peek() {
+    let sign_length = match input.peek_at(1) {
+      Ok('+') | Ok('-') => 1,
+      _ => 0,
+    };
+
+    if is_number_at(input, 1 + sign_length) {
+      for _ in 0..=sign_length {
+        literal.push(input.next()?);
+      }
+      literal.push_str(&read_digits(input, 10)?);
+      is_real = true;
+    }
+  }
 
-  match str::parse::<i32>(&digits_as_str) {
-    Ok(i) => Ok(Token::Literal(LiteralValue::IntLiteral(i))),
-    Err(_) => Err(LexerError::InvalidNumberLiteral),
+  if is_real {
+    return match str::parse::<f64>(&without_separators(&literal)) {
+      Ok(r) if r.is_finite() => Ok(Token::Literal(LiteralValue::RealLiteral(r))),
+      _ => Err(LexerError::InvalidNumberLiteral),
+    };
   }
+
+  parse_int_literal(literal, 0, 10)
 }
 
-/// Parses the next token from the input stream.
-fn next_token(input: &mut CharStream, logger: Rc<Logger>) -> Result<TokenWithCtx, LexerError> {
-  // Skip whitespace
-  input.advance_until(|ch| !is_whitespace(ch));
+// Skips a block comment. Block comments nest, so that a region containing comments can be
+// commented out.
+fn skip_block_comment(input: &mut CharStream) -> Result<(), LexerError> {
+  let mut depth = 0;
+
+  loop {
+    match (input.peek(), input.peek_at(1)) {
+      (Ok('/'), Ok('*')) => {
+        input.advance();
+        input.advance();
+        depth += 1;
+      }
+      (Ok('*'), Ok('/')) => {
+        input.advance();
+        input.advance();
+        depth -= 1;
+
+        if depth == 0 {
+          return Ok(());
+        }
+      }
+      (Ok(_), _) => input.advance(),
+      (Err(_), _) => return Err(LexerError::UnterminatedComment),
+    }
+  }
+}
+
+/// Skips whitespace and comments. Returns the documentation comments ("///") before the next
+/// token, without the slashes, one line each.
+fn skip_trivia(input: &mut CharStream) -> Result<Option<String>, LexerErrorWithCtx> {
+  let mut doc_lines: Vec<String> = Vec::new();
+
+  loop {
+    input.advance_until(|ch| !is_whitespace(ch));
+
+    let offset = input.offset();
+
+    match (input.peek(), input.peek_at(1)) {
+      (Ok('/'), Ok('/')) => {
+        let line: String = input.take_until(|ch| ch == '\n').iter().collect();
+
+        // Like in Rust, four or more slashes make an ordinary comment.
+        if line.starts_with("///") && !line.starts_with("////") {
+          let text = &line[3..];
+          let text = text.strip_prefix(' ').unwrap_or(text);
+          doc_lines.push(text.trim_end().to_string());
+        }
+      }
+      (Ok('/'), Ok('*')) => skip_block_comment(input).with_ctx(offset)?,
+      _ => break,
+    }
+  }
+
+  if doc_lines.is_empty() {
+    Ok(None)
+  } else {
+    Ok(Some(doc_lines.join("\n")))
+  }
+}
 
+/// Parses the next token from the input stream, which must be positioned after any whitespace
+/// and comments. Errors point at the start of the offending token.
+fn next_token(
+  input: &mut CharStream,
+  logger: Rc<dyn Logger>,
+) -> Result<TokenWithCtx, LexerErrorWithCtx> {
   if input.reached_end() {
     return Ok(TokenWithCtx {
-      offset: input.offset,
+      offset: input.offset(),
       token: Token::EndOfFile,
     });
   }
 
-  let offset = input.offset;
+  let offset = input.offset();
 
-  let with_ctx =
-    |token: Result<Token, LexerError>| token.map(|token| TokenWithCtx { offset, token });
+  let with_ctx = |token: Result<Token, LexerError>| {
+    token
+      .map(|token| TokenWithCtx { offset, token })
+      .with_ctx(offset)
+  };
 
-  let first = input.peek()?;
+  let first = input.peek().map_err(LexerError::from).with_ctx(offset)?;
 
   let token = match first {
-    ';' | '(' | ')' | '+' | '-' | '*' | '<' | '=' | '&' | '!' => {
+    ';' | ',' | '(' | ')' | '[' | ']' | '+' | '-' | '*' | '/' | '%' | '=' | '&' | '|' | '!' => {
       input.advance();
       with_ctx(Ok(parse_single_char_token(first)))
     }
+    '<' | '>' => {
+      input.advance();
+
+      let op = match (first, input.peek()) {
+        ('<', Ok('=')) => Some(BinaryOperator::LessThanOrEqual),
+        ('<', Ok('>')) => Some(BinaryOperator::NotEqual),
+        ('>', Ok('=')) => Some(BinaryOperator::GreaterThanOrEqual),
+        _ => None,
+      };
+
+      match op {
+        Some(op) => {
+          input.advance();
+          with_ctx(Ok(Token::Operator(Operator::BinaryOperator(op))))
+        }
+        None => with_ctx(Ok(parse_single_char_token(first))),
+      }
+    }
     ':' => {
       input.advance();
 
-      if input.peek()? == '=' {
+      if let Ok('=') = input.peek() {
         input.advance();
         with_ctx(Ok(Token::Assign))
       } else {
@@ -137,55 +406,16 @@ fn next_token(input: &mut CharStream, logger: Rc<Logger>) -> Result<TokenWithCtx
     }
     '.' => {
       input.advance();
-      let next = input.peek()?;
-      if next == '.' {
+      if let Ok('.') = input.peek() {
         input.advance();
         with_ctx(Ok(Token::Range))
       } else {
-        with_ctx(Err(LexerError::UnknownToken(next.to_string())))
+        with_ctx(Ok(Token::Dot))
       }
     }
-    '0'...'9' => with_ctx(read_number_literal(input)),
-    '"' => with_ctx(read_string_literal(input)),
-    '/' => {
-      input.advance();
-      let next = input.peek()?;
-
-      if next == '/' {
-        input.advance();
-        // If this is a single line comment, skip until the next newline
-        input.advance_until(|ch| ch == '\n');
-        // Recursively call self to get the next token
-        next_token(input, logger.clone())
-      } else if next == '*' {
-        input.advance();
-
-        let mut prev = input.peek()?;
-        input.advance();
-
-        loop {
-          if input.reached_end() {
-            return Err(LexerError::UnterminatedComment);
-          }
-
-          let next = input.peek()?;
-          input.advance();
-
-          if prev == '*' && next == '/' {
-            break;
-          }
-
-          prev = next;
-        }
-
-        next_token(input, logger.clone())
-      } else {
-        with_ctx(Ok(Token::Operator(Operator::BinaryOperator(
-          BinaryOperator::Div,
-        ))))
-      }
-    }
-    'A'...'Z' | 'a'...'z' => with_ctx(read_keyword_or_identifier(input)),
+    '0'..='9' => with_ctx(read_number_literal(input)),
+    '"' => with_ctx(read_string_literal(input, logger.clone())),
+    first if is_identifier_start(first) => with_ctx(read_keyword_or_identifier(input)),
     first => with_ctx(Err(LexerError::UnknownToken(first.to_string()))),
   };
 
@@ -197,14 +427,17 @@ fn next_token(input: &mut CharStream, logger: Rc<Logger>) -> Result<TokenWithCtx
 pub struct BufferedLexer {
   stream: CharStream,
   token: Option<TokenWithCtx>,
-  logger: Rc<Logger>,
+  // The documentation comment before the buffered token.
+  doc_comment: Option<String>,
+  logger: Rc<dyn Logger>,
 }
 
 impl BufferedLexer {
-  pub fn new(stream: CharStream, logger: Rc<Logger>) -> BufferedLexer {
+  pub fn new(stream: CharStream, logger: Rc<dyn Logger>) -> BufferedLexer {
     BufferedLexer {
       stream,
       token: None,
+      doc_comment: None,
       logger,
     }
   }
@@ -217,6 +450,7 @@ impl TokenStream for BufferedLexer {
       self.stream.advance();
     } else {
       self.token = None;
+      self.doc_comment = None;
     }
   }
 
@@ -230,7 +464,7 @@ impl TokenStream for BufferedLexer {
   fn offset(&self) -> usize {
     match self.token {
       Some(ref token) => token.offset,
-      _ => self.stream.offset,
+      _ => self.stream.offset(),
     }
   }
 
@@ -239,13 +473,18 @@ impl TokenStream for BufferedLexer {
     if self.token.is_some() {
       Ok(self.token.clone().unwrap())
     } else {
-      let start_pos = self.offset();
-      let next = next_token(&mut self.stream, self.logger.clone()).with_ctx(start_pos)?;
+      let doc_comment = skip_trivia(&mut self.stream)?;
+      let next = next_token(&mut self.stream, self.logger.clone())?;
       self.token = Some(next.clone());
+      self.doc_comment = doc_comment;
       Ok(next)
     }
   }
 
+  fn doc_comment(&self) -> Option<String> {
+    self.doc_comment.clone()
+  }
+
   /// Same as `peek()`, followed by `advance()`.
   fn next(&mut self) -> Result<TokenWithCtx, LexerErrorWithCtx> {
     let token = self.peek()?;
@@ -256,9 +495,10 @@ impl TokenStream for BufferedLexer {
 
 #[cfg(test)]
 mod tests {
-  use common::errors::LexerError;
+  use common::errors::{ErrWithCtx, LexerError};
   use parsing::lexer_test_util::*;
   use parsing::token::Token::*;
+  use parsing::token_stream::TokenStream;
 
   #[test]
   pub fn basic_expression() {
@@ -284,12 +524,209 @@ mod tests {
     assert_eq!(tokens, [Colon, equal_op(), Assign]);
   }
 
+  #[test]
+  pub fn comparison_operators() {
+    use common::types::BinaryOperator::*;
+    use common::types::Operator::BinaryOperator;
+
+    let tokens = lex("< <= > >= = <> | <<>").expect("Should parse.");
+    let expected: Vec<_> = [
+      LessThan,
+      LessThanOrEqual,
+      GreaterThan,
+      GreaterThanOrEqual,
+      Equal,
+      NotEqual,
+      Or,
+      LessThan,
+      NotEqual,
+    ].iter()
+      .map(|op| Operator(BinaryOperator(*op)))
+      .collect();
+    assert_eq!(tokens, expected);
+  }
+
+  #[test]
+  pub fn bool_literals() {
+    use common::types::LiteralValue::BoolLiteral;
+
+    let tokens = lex("true false truth").expect("Should parse.");
+    assert_eq!(
+      tokens,
+      [
+        Literal(BoolLiteral(true)),
+        Literal(BoolLiteral(false)),
+        variable("truth")
+      ]
+    );
+  }
+
+  #[test]
+  pub fn real_literals() {
+    use common::types::LiteralValue::RealLiteral;
+
+    let tokens = lex("1.25 1e-3 2.5E+2 7e2").expect("Should parse.");
+    assert_eq!(
+      tokens,
+      [
+        Literal(RealLiteral(1.25)),
+        Literal(RealLiteral(0.001)),
+        Literal(RealLiteral(250.0)),
+        Literal(RealLiteral(700.0)),
+      ]
+    );
+  }
+
+  #[test]
+  pub fn integer_range_is_not_real() {
+    let tokens = lex("1..2").expect("Should parse.");
+    assert_eq!(tokens, [number(1), Range, number(2)]);
+  }
+
+  #[test]
+  pub fn exponent_requires_digits() {
+    let tokens = lex("2e 3.x").expect("Should parse.");
+    assert_eq!(tokens, [number(2), variable("e"), number(3), Dot, variable("x")]);
+  }
+
+  #[test]
+  pub fn radix_literals() {
+    let tokens = lex("0x1F 0XfF 0b1010 0o17 0 007").expect("Should parse.");
+    assert_eq!(
+      tokens,
+      [number(31), number(255), number(10), number(15), number(0), number(7)]
+    );
+  }
+
+  #[test]
+  pub fn digit_separators() {
+    use common::types::LiteralValue::RealLiteral;
+
+    let tokens = lex("1_000_000 0xFF_FF 0b1_0 1_0.2_5e1_0").expect("Should parse.");
+    assert_eq!(
+      tokens,
+      [
+        number(1_000_000),
+        number(0xFFFF),
+        number(2),
+        Literal(RealLiteral(10.25e10)),
+      ]
+    );
+  }
+
+  #[test]
+  pub fn misplaced_digit_separators() {
+    for literal in &["1_", "1__0", "0x_1", "0b", "0x", "1_.5", "1.5_"] {
+      assert_match!(lex(literal) => Err(LexerError::InvalidNumberLiteral));
+    }
+  }
+
+  #[test]
+  pub fn radix_digits_end_the_literal() {
+    let tokens = lex("0b102 0o78").expect("Should parse.");
+    assert_eq!(tokens, [number(2), number(2), number(7), number(8)]);
+  }
+
+  #[test]
+  pub fn integer_overflow_reports_the_excess() {
+    match lex("2_147_483_650") {
+      Err(LexerError::IntegerLiteralOverflow { literal, excess, .. }) => {
+        assert_eq!((literal.as_str(), excess), ("2_147_483_650", Some(3)));
+      }
+      result => panic!("Expected an overflow, was {:?}", result),
+    }
+
+    let result = lex("0xFFFF_FFFF");
+    assert_match!(result => Err(LexerError::IntegerLiteralOverflow {
+      excess: Some(2_147_483_648),
+      ..
+    }));
+
+    let result = lex("1000000000000000000000000000000000000000");
+    assert_match!(result => Err(LexerError::IntegerLiteralOverflow { excess: None, .. }));
+  }
+
+  #[test]
+  pub fn errors_point_at_the_literal() {
+    let mut lexer = create_lexer("x :=\n  99999999999;");
+    lexer.next().expect("Should parse.");
+    lexer.next().expect("Should parse.");
+    assert_match!(lexer.next() => Err(ErrWithCtx(LexerError::IntegerLiteralOverflow { .. }, 7)));
+  }
+
+  #[test]
+  pub fn nested_block_comments() {
+    use common::types::BinaryOperator::Div;
+    use common::types::Operator::BinaryOperator;
+
+    let tokens = lex("6 /* a /* b */ c /**/ */ / 2").expect("Should parse.");
+    assert_eq!(tokens, [number(6), Operator(BinaryOperator(Div)), number(2)]);
+  }
+
+  #[test]
+  pub fn unterminated_nested_block_comment() {
+    let mut lexer = create_lexer("x\n  /* a /* b */");
+    lexer.next().expect("Should parse.");
+    assert_match!(lexer.next() => Err(ErrWithCtx(LexerError::UnterminatedComment, 4)));
+  }
+
+  #[test]
+  pub fn doc_comments_are_kept() {
+    let mut lexer = create_lexer("/// First\n///Second \n// Plain\n//// Plain\nvar x");
+    assert_eq!(lexer.peek().expect("Should parse.").token, Var);
+    assert_eq!(lexer.doc_comment(), Some("First\nSecond".to_string()));
+
+    lexer.next().expect("Should parse.");
+    assert_eq!(lexer.peek().expect("Should parse.").token, variable("x"));
+    assert_eq!(lexer.doc_comment(), None);
+  }
+
   #[test]
   pub fn string_escape_codes() {
     let tokens = lex(r#""\r\n\\\"\t""#).expect("Should parse.");
     assert_eq!(tokens, [string("\r\n\\\"\t")]);
   }
 
+  #[test]
+  pub fn interpolated_string() {
+    use parsing::token::StringPart::*;
+    use parsing::token::TokenWithCtx;
+
+    let tokens = lex(r#""a${x + 1}b\${y}""#).expect("Should parse.");
+    let code = vec![
+      TokenWithCtx {
+        offset: 4,
+        token: variable("x"),
+      },
+      TokenWithCtx {
+        offset: 6,
+        token: add_op(),
+      },
+      TokenWithCtx {
+        offset: 8,
+        token: number(1),
+      },
+      TokenWithCtx {
+        offset: 9,
+        token: EndOfFile,
+      },
+    ];
+    assert_eq!(
+      tokens,
+      [InterpolatedString(vec![
+        Text("a".to_string()),
+        Code(code),
+        Text("b${y}".to_string()),
+      ])]
+    );
+  }
+
+  #[test]
+  pub fn unterminated_interpolation() {
+    let result = lex(r#""a${x"#);
+    assert_match!(result => Err(LexerError::UnterminatedStringLiteral));
+  }
+
   #[test]
   pub fn malformed_string() {
     let result = lex(r#""Hello, world!; stuff"#);
@@ -307,4 +744,51 @@ mod tests {
     let result = lex("3a").expect("Should parse");
     assert_eq!(result, [number(3), variable("a")]);
   }
+
+  #[test]
+  pub fn unicode_identifiers() {
+    let result = lex("äiti öljy_2 Ärrä Ωmega 名前").expect("Should parse.");
+    assert_eq!(
+      result,
+      [
+        variable("äiti"),
+        variable("öljy_2"),
+        variable("Ärrä"),
+        variable("Ωmega"),
+        variable("名前"),
+      ]
+    );
+  }
+
+  #[test]
+  pub fn identifiers_continue_with_combining_marks() {
+    // "a" followed by a combining diaeresis.
+    let result = lex("a\u{308}b").expect("Should parse.");
+    assert_eq!(result, [variable("a\u{308}b")]);
+  }
+
+  #[test]
+  pub fn keywords_are_not_prefixes_of_identifiers() {
+    let result = lex("varä printö").expect("Should parse.");
+    assert_eq!(result, [variable("varä"), variable("printö")]);
+  }
+
+  #[test]
+  pub fn symbols_between_ascii_letters_are_not_identifiers() {
+    // These are between 'Z' and 'a' in ASCII.
+    for symbol in &["\\", "^", "`"] {
+      let result = lex(symbol);
+      assert_match!(result => Err(LexerError::UnknownToken(_)));
+    }
+
+    let result = lex("a[b]").expect("Should parse.");
+    assert_eq!(result, [variable("a"), LBracket, variable("b"), RBracket]);
+  }
+
+  #[test]
+  pub fn identifiers_dont_start_with_underscores_or_marks() {
+    assert_match!(lex("_a") => Err(LexerError::UnknownToken(_)));
+    assert_match!(lex("\u{308}a") => Err(LexerError::UnknownToken(_)));
+    assert_match!(lex("a€") => Err(LexerError::UnknownToken(_)));
+  }
 }
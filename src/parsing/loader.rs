@@ -0,0 +1,149 @@
+// Loads a program and the files it imports into a single statement list.
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use common::errors::*;
+use common::logger::Logger;
+use diagnostics::file_context::FileContextSource;
+use parsing::ast::*;
+use parsing::char_stream::CharStream;
+use parsing::lexer::BufferedLexer;
+use parsing::parser::Parser;
+
+pub struct Loader<'a> {
+  sources: &'a mut FileContextSource,
+  logger: Rc<dyn Logger>,
+  // The files which are currently being loaded, outermost first. Importing one of them again is
+  // a cycle.
+  loading: Vec<PathBuf>,
+  // Every file is merged into the program at most once, so that a file may be imported from
+  // several places.
+  loaded: HashSet<PathBuf>,
+}
+
+impl<'a> Loader<'a> {
+  pub fn new(sources: &'a mut FileContextSource, logger: Rc<dyn Logger>) -> Loader<'a> {
+    Loader {
+      sources,
+      logger,
+      loading: Vec::new(),
+      loaded: HashSet::new(),
+    }
+  }
+
+  /// Loads the main program. Imports are resolved relative to the directory of `file_name`, or
+  /// relative to the working directory if the program isn't read from a file.
+  pub fn load(
+    &mut self,
+    source: &str,
+    file_name: Option<String>,
+  ) -> Result<Vec<StatementWithCtx>, ParserErrors> {
+    let directory = match file_name {
+      Some(ref file_name) => {
+        let path = Path::new(file_name);
+        if let Ok(canonical) = path.canonicalize() {
+          self.loaded.insert(canonical.clone());
+          self.loading.push(canonical);
+        }
+        path.parent().map(Path::to_path_buf).unwrap_or_default()
+      }
+      None => PathBuf::new(),
+    };
+
+    self.load_source(source, file_name, &directory)
+  }
+
+  fn load_source(
+    &mut self,
+    source: &str,
+    file_name: Option<String>,
+    directory: &Path,
+  ) -> Result<Vec<StatementWithCtx>, ParserErrors> {
+    let file_id = self.sources.add_file(source, file_name);
+
+    let stream = CharStream::with_base_offset(source, self.sources.base_offset(file_id));
+    let lexer = BufferedLexer::new(stream, self.logger.clone());
+    let mut parser = Parser::new(lexer, self.logger.clone());
+
+    let imports = parser.parse_imports()?;
+    let program = parser.parse_program();
+
+    // Imported declarations precede the statements of the importing file.
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+
+    for import in imports {
+      match self.load_import(&import, directory) {
+        Ok(mut imported) => statements.append(&mut imported),
+        Err(mut import_errors) => errors.append(&mut import_errors),
+      }
+    }
+
+    match program {
+      Ok(mut program) => statements.append(&mut program),
+      Err(mut program_errors) => errors.append(&mut program_errors),
+    }
+
+    if errors.is_empty() {
+      Ok(statements)
+    } else {
+      Err(errors)
+    }
+  }
+
+  fn load_import(
+    &mut self,
+    import: &Import,
+    directory: &Path,
+  ) -> Result<Vec<StatementWithCtx>, ParserErrors> {
+    let offset = import.source_position.start;
+    let path = directory.join(&import.path);
+
+    let unreadable = |err: ::std::io::Error| {
+      vec![ErrWithCtx(
+        ParserError::UnreadableImport {
+          path: import.path.clone(),
+          reason: err.to_string(),
+        },
+        offset,
+      )]
+    };
+
+    let canonical = path.canonicalize().map_err(unreadable)?;
+
+    if self.loading.contains(&canonical) {
+      return Err(vec![ErrWithCtx(
+        ParserError::CyclicImport(import.path.clone()),
+        offset,
+      )]);
+    }
+
+    if !self.loaded.insert(canonical.clone()) {
+      return Ok(Vec::new());
+    }
+
+    let source = fs::read_to_string(&canonical).map_err(unreadable)?;
+    let directory = canonical.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    self.loading.push(canonical);
+    let statements = self.load_source(&source, Some(path.display().to_string()), &directory);
+    self.loading.pop();
+
+    // Only the declarations of an imported file are merged into the program, so it can't run
+    // code of its own.
+    let statements = statements?;
+    let errors: ParserErrors = statements
+      .iter()
+      .filter(|statement| !statement.statement.is_declaration())
+      .map(|statement| ErrWithCtx(ParserError::StatementInImport, statement.source_position.start))
+      .collect();
+
+    if errors.is_empty() {
+      Ok(statements)
+    } else {
+      Err(errors)
+    }
+  }
+}
@@ -5,6 +5,7 @@ pub mod token_stream;
 pub mod lexer;
 pub mod parser;
 pub mod ast;
+pub mod loader;
 
 #[cfg(test)]
 mod lexer_test_util;
@@ -8,15 +8,15 @@ use common::util::{ResultExt, VecExt};
 
 use parsing::ast::*;
 use parsing::token::*;
-use parsing::token_stream::TokenStream;
+use parsing::token_stream::{TokenBuffer, TokenStream};
 
 pub struct Parser<T: TokenStream> {
   lexer: T,
-  logger: Rc<Logger>,
+  logger: Rc<dyn Logger>,
 }
 
 impl<T: TokenStream> Parser<T> {
-  pub fn new(lexer: T, logger: Rc<Logger>) -> Parser<T> {
+  pub fn new(lexer: T, logger: Rc<dyn Logger>) -> Parser<T> {
     Parser { lexer, logger }
   }
 
@@ -34,6 +34,16 @@ impl<T: TokenStream> Parser<T> {
     }
   }
 
+  // Advances past the next token if it is equal to `token`.
+  fn accept(&mut self, token: &Token) -> Result<bool, ParserErrorWithCtx> {
+    if self.lexer.peek()?.token == *token {
+      self.advance()?;
+      Ok(true)
+    } else {
+      Ok(false)
+    }
+  }
+
   fn expect_identifier(&mut self) -> Result<String, ParserErrorWithCtx> {
     let next = self.lexer.peek()?;
     match next.token {
@@ -55,6 +65,27 @@ impl<T: TokenStream> Parser<T> {
         self.advance()?;
         Ok(type_name)
       }
+      Token::Array => {
+        self.advance()?;
+        self.expect_eq(&Token::LBracket)?;
+
+        let length_token = self.lexer.next()?;
+        let length = match length_token.token {
+          Token::Literal(LiteralValue::IntLiteral(length)) if length > 0 => length as usize,
+          _ => return Err(ParserError::InvalidArrayLength).with_ctx(length_token.offset),
+        };
+
+        self.expect_eq(&Token::RBracket)?;
+        self.expect_eq(&Token::Of)?;
+
+        let element = self.expect_type_name()?;
+        Ok(TypeName::ArrayType(Box::new(element), length))
+      }
+      // User-defined types are resolved by the type checker.
+      Token::Identifier(name) => {
+        self.advance()?;
+        Ok(TypeName::NamedType(name))
+      }
       other => Err(ParserError::UnexpectedToken {
         expected: TokenKind::TypeK,
         was: other.get_kind(),
@@ -68,36 +99,76 @@ impl<T: TokenStream> Parser<T> {
   }
 
   fn parse_print_statement(&mut self) -> Result<Statement, ParserErrorWithCtx> {
-    self.expect_eq(&Token::Print)?;
+    let newline = self.accept(&Token::PrintLn)?;
 
-    let value = self.parse_expression()?;
+    if !newline {
+      self.expect_eq(&Token::Print)?;
+    }
+
+    let mut arguments = Vec::new();
+
+    // println without arguments only prints a newline.
+    if !newline || self.lexer.peek()?.token != Token::Semicolon {
+      arguments.push(self.parse_print_argument()?);
+
+      while self.accept(&Token::Comma)? {
+        arguments.push(self.parse_print_argument()?);
+      }
+    }
 
     self.expect_eq(&Token::Semicolon)?;
 
-    Ok(Statement::Print(value))
+    Ok(Statement::Print { arguments, newline })
+  }
+
+  fn parse_print_argument(&mut self) -> Result<PrintArgument, ParserErrorWithCtx> {
+    let value = self.parse_expression()?;
+
+    let width = if self.accept(&Token::Colon)? {
+      Some(self.parse_expression()?)
+    } else {
+      None
+    };
+
+    Ok(PrintArgument { value, width })
   }
 
   fn parse_read_statement(&mut self) -> Result<Statement, ParserErrorWithCtx> {
     self.expect_eq(&Token::Read)?;
 
-    let identifier = self.expect_identifier()?;
+    let mut identifiers = vec![self.expect_identifier()?];
+
+    while self.lexer.peek()?.token == Token::Comma {
+      self.advance()?;
+      identifiers.push(self.expect_identifier()?);
+    }
 
     self.expect_eq(&Token::Semicolon)?;
 
-    Ok(Statement::Read(identifier))
+    Ok(Statement::Read(identifiers))
   }
 
+  // Parses both variable and constant declarations.
   fn parse_decleration(&mut self) -> Result<Statement, ParserErrorWithCtx> {
-    self.expect_eq(&Token::Var)?;
+    let is_constant = self.lexer.peek()?.token == Token::Const;
+    let doc = self.lexer.doc_comment();
+    self.expect_eq(if is_constant { &Token::Const } else { &Token::Var })?;
 
     let name = self.expect_identifier()?;
 
-    self.expect_eq(&Token::Colon)?;
-
-    let type_of = self.expect_type_name()?;
-
-    let initial_value = if self.lexer.peek()?.token == Token::Assign {
+    let type_of = if self.lexer.peek()?.token == Token::Colon {
       self.advance()?;
+      Some(self.expect_type_name()?)
+    } else {
+      None
+    };
+
+    // Constants must be initialised, and so must variables with an inferred type.
+    let initial_value = if is_constant
+      || type_of.is_none()
+      || self.lexer.peek()?.token == Token::Assign
+    {
+      self.expect_eq(&Token::Assign)?;
 
       let expression = self.parse_expression()?;
       Some(expression)
@@ -111,19 +182,126 @@ impl<T: TokenStream> Parser<T> {
       name,
       type_of,
       initial: initial_value,
+      is_constant,
+      doc,
     })
   }
 
-  fn parse_assignment(&mut self) -> Result<Statement, ParserErrorWithCtx> {
+  // Parses a parenthesised, comma separated (and possibly empty) list of items.
+  fn parse_parenthesised_list<U, F>(&mut self, parse_item: F) -> Result<Vec<U>, ParserErrorWithCtx>
+  where
+    F: Fn(&mut Self) -> Result<U, ParserErrorWithCtx>,
+  {
+    self.expect_eq(&Token::LParen)?;
+
+    let mut items = Vec::new();
+
+    if self.lexer.peek()?.token == Token::RParen {
+      self.advance()?;
+      return Ok(items);
+    }
+
+    loop {
+      items.push(parse_item(self)?);
+
+      if self.lexer.peek()?.token == Token::Comma {
+        self.advance()?;
+      } else {
+        self.expect_eq(&Token::RParen)?;
+        return Ok(items);
+      }
+    }
+  }
+
+  fn parse_arguments(&mut self) -> Result<Vec<Expression>, ParserErrorWithCtx> {
+    self.parse_parenthesised_list(|parser| parser.parse_expression())
+  }
+
+  // Parses a bracketed index expression.
+  fn parse_index(&mut self) -> Result<Expression, ParserErrorWithCtx> {
+    self.expect_eq(&Token::LBracket)?;
+    let index = self.parse_expression()?;
+    self.expect_eq(&Token::RBracket)?;
+    Ok(index)
+  }
+
+  // Parses either `[index]` or `[start..end]` following the indexed expression.
+  fn parse_index_or_slice(&mut self, target: Expression) -> Result<Expression, ParserErrorWithCtx> {
+    self.expect_eq(&Token::LBracket)?;
+    let index = self.parse_expression()?;
+
+    let expression = if self.lexer.peek()?.token == Token::Range {
+      self.advance()?;
+      let end = self.parse_expression()?;
+      Expression::Slice(Box::new((target, index, end)))
+    } else {
+      Expression::Index(Box::new((target, index)))
+    };
+
+    self.expect_eq(&Token::RBracket)?;
+    Ok(expression)
+  }
+
+  // Interpolated expressions are parsed separately from the rest of the expression.
+  fn parse_interpolation_part(
+    &self,
+    part: StringPart,
+  ) -> Result<InterpolationPart, ParserErrorWithCtx> {
+    match part {
+      StringPart::Text(text) => Ok(InterpolationPart::Text(text)),
+      StringPart::Code(tokens) => {
+        let mut parser = Parser::new(TokenBuffer::new(tokens), self.logger.clone());
+        let value = parser.parse_expression()?;
+        parser.expect_eq(&Token::EndOfFile)?;
+        Ok(InterpolationPart::Value(value))
+      }
+    }
+  }
+
+  fn parse_field_access(&mut self) -> Result<String, ParserErrorWithCtx> {
+    self.expect_eq(&Token::Dot)?;
+    self.expect_identifier()
+  }
+
+  // Indexing and field access bind tighter than any operator, so they are applied to an operand
+  // as soon as it has been parsed.
+  fn parse_postfix(&mut self, mut operand: Expression) -> Result<Expression, ParserErrorWithCtx> {
+    loop {
+      operand = match self.lexer.peek()?.token {
+        Token::LBracket => self.parse_index_or_slice(operand)?,
+        Token::Dot => Expression::Field(Box::new(operand), self.parse_field_access()?),
+        _ => return Ok(operand),
+      };
+    }
+  }
+
+  // Both assignments and procedure calls start with an identifier.
+  fn parse_assignment_or_call(&mut self) -> Result<Statement, ParserErrorWithCtx> {
     let identifier = self.expect_identifier()?;
 
+    if self.lexer.peek()?.token == Token::LParen {
+      let arguments = self.parse_arguments()?;
+      self.expect_eq(&Token::Semicolon)?;
+      return Ok(Statement::Call(identifier, arguments));
+    }
+
+    let mut target = LValue::Variable(identifier);
+
+    loop {
+      target = match self.lexer.peek()?.token {
+        Token::LBracket => LValue::Index(Box::new(target), self.parse_index()?),
+        Token::Dot => LValue::Field(Box::new(target), self.parse_field_access()?),
+        _ => break,
+      };
+    }
+
     self.expect_eq(&Token::Assign)?;
 
     let value = self.parse_expression()?;
 
     self.expect_eq(&Token::Semicolon)?;
 
-    Ok(Statement::Assign(identifier, value))
+    Ok(Statement::Assign(target, value))
   }
 
   // Parses expressions using a modified version of the shunting yard algorithm.
@@ -139,6 +317,9 @@ impl<T: TokenStream> Parser<T> {
 
     let start = self.lexer.peek()?.offset;
 
+    // True when the next token should begin an operand, which means that "-" is a prefix minus.
+    let mut expects_operand = true;
+
     // We don't need to access this from outside, so this function can be local.
     fn create_node(operator: Operator, output: &mut Vec<Expression>) -> Result<(), ParserError> {
       let node = match operator {
@@ -158,25 +339,108 @@ impl<T: TokenStream> Parser<T> {
       Ok(())
     }
 
+    let negate = OpStackItem::Operator(Operator::UnaryOperator(UnaryOperator::Negate));
+
     loop {
-      let next = self.lexer.peek()?;
+      let next = match self.lexer.peek() {
+        Ok(next) => next,
+        // The lexer measures overflow from i32::MAX, but a negated literal may be one larger.
+        Err(ErrWithCtx(LexerError::IntegerLiteralOverflow { literal, excess, .. }, offset))
+          if expects_operand && operators.last() == Some(&negate) =>
+        {
+          let error = LexerError::IntegerLiteralOverflow {
+            literal,
+            is_negated: true,
+            excess: excess.map(|excess| excess - 1),
+          };
+          return Err(ParserError::LexerError(error)).with_ctx(offset);
+        }
+        Err(err) => return Err(err.into()),
+      };
 
       match next.token {
         // Literals are just pushed to the output stack
         Token::Literal(value) => {
           self.advance()?;
-          output.push(Expression::Literal(value));
+          expects_operand = false;
+
+          let is_negated = operators.last() == Some(&negate);
+
+          // Negative integer literals are folded into a single literal.
+          let value = match value {
+            LiteralValue::IntLiteral(i) if is_negated => {
+              operators.pop();
+              LiteralValue::IntLiteral(-i)
+            }
+            value => value,
+          };
+
+          output.push(self.parse_postfix(Expression::Literal(value))?);
+        }
+        // This is the only way to write i32::MIN.
+        Token::MinIntMagnitude(literal) => {
+          if operators.last() != Some(&negate) {
+            let error = LexerError::IntegerLiteralOverflow {
+              literal,
+              is_negated: false,
+              excess: Some(1),
+            };
+            return Err(ParserError::LexerError(error)).with_ctx(next.offset);
+          }
+
+          self.advance()?;
+          expects_operand = false;
+
+          operators.pop();
+          let operand = Expression::Literal(LiteralValue::IntLiteral(i32::MIN));
+          output.push(self.parse_postfix(operand)?);
         }
         Token::Identifier(identifier) => {
           self.advance()?;
-          output.push(Expression::Variable(identifier));
+          expects_operand = false;
+
+          let operand = if self.lexer.peek()?.token == Token::LParen {
+            let arguments = self.parse_arguments()?;
+            Expression::Call(identifier, arguments)
+          } else {
+            Expression::Variable(identifier)
+          };
+
+          output.push(self.parse_postfix(operand)?);
+        }
+        Token::InterpolatedString(parts) => {
+          self.advance()?;
+          expects_operand = false;
+
+          let parts = parts
+            .into_iter()
+            .map(|part| self.parse_interpolation_part(part))
+            .collect::<Result<Vec<_>, _>>()?;
+
+          output.push(self.parse_postfix(Expression::Interpolation(parts))?);
+        }
+        // A type name followed by a parenthesised expression is a conversion.
+        Token::Type(type_of) => {
+          self.advance()?;
+          expects_operand = false;
+
+          self.expect_eq(&Token::LParen)?;
+          let inner = self.parse_expression()?;
+          self.expect_eq(&Token::RParen)?;
+
+          output.push(self.parse_postfix(Expression::Convert(type_of, Box::new(inner)))?);
         }
+        // A right parenthesis without a matching left parenthesis ends the expression.
+        // This happens when the expression is the last argument of a call.
+        Token::RParen if !operators.contains(&OpStackItem::LParen) => break,
         Token::LParen => {
           self.advance()?;
+          expects_operand = true;
           operators.push(OpStackItem::LParen);
         }
         Token::RParen => {
           self.advance()?;
+          expects_operand = false;
           // If we encounter an error during the pop_while loop, we'll store the error in this.
           let mut result = Ok(());
 
@@ -198,12 +462,27 @@ impl<T: TokenStream> Parser<T> {
 
           // Pop the left parenthesis.
           operators.pop();
+
+          // The parenthesised expression is the last operand.
+          if let Some(operand) = output.pop() {
+            output.push(self.parse_postfix(operand)?);
+          }
         }
         // When an operator is encountered, we need to make sure operator precedence holds.
         // This means that if previously added operator(s) have highers precedence, we must
         // handle them before adding this to the operator stack.
         Token::Operator(op) => {
           self.advance()?;
+
+          let op = match op {
+            Operator::BinaryOperator(BinaryOperator::Sub) if expects_operand => {
+              Operator::UnaryOperator(UnaryOperator::Negate)
+            }
+            op => op,
+          };
+
+          expects_operand = true;
+
           // If we encounter an error during the pop_while loop, we'll store the error in this.
           let mut result = Ok(());
 
@@ -215,8 +494,15 @@ impl<T: TokenStream> Parser<T> {
             match **op_op_lparen {
               OpStackItem::LParen => false,
               OpStackItem::Operator(stack_op) => {
-                // When we encounter an operator with lower or equal precedence, stop.
-                if stack_op.get_precedence() <= op.get_precedence() {
+                // When we encounter an operator with lower precedence, stop. Binary operators
+                // are left associative, so operators with equal precedence are popped too.
+                // Prefix operators don't have a left operand, so they can't pop anything.
+                let stops = match op.get_arity() {
+                  Arity::Binary => stack_op.get_precedence() < op.get_precedence(),
+                  Arity::Unary => true,
+                };
+
+                if stops {
                   false
                 } else {
                   // Mark this operator to be popped, and create the AST node for it.
@@ -272,10 +558,20 @@ impl<T: TokenStream> Parser<T> {
 
     let from = self.parse_expression().vec_err()?;
 
-    self.expect_eq(&Token::Range).vec_err()?;
+    let is_descending = self.accept(&Token::DownTo).vec_err()?;
+
+    if !is_descending {
+      self.expect_eq(&Token::Range).vec_err()?;
+    }
 
     let to = self.parse_expression().vec_err()?;
 
+    let step = if self.accept(&Token::Step).vec_err()? {
+      Some(self.parse_expression().vec_err()?)
+    } else {
+      None
+    };
+
     self.expect_eq(&Token::Do).vec_err()?;
 
     let run = self.parse_statement_list()?;
@@ -288,19 +584,244 @@ impl<T: TokenStream> Parser<T> {
       variable,
       from,
       to,
+      step,
+      is_descending,
       run,
     })
   }
 
+  pub fn parse_if(&mut self) -> Result<Statement, ParserErrors> {
+    self.expect_eq(&Token::If).vec_err()?;
+
+    let condition = self.parse_expression().vec_err()?;
+
+    self.expect_eq(&Token::Then).vec_err()?;
+
+    let then_branch = self.parse_statement_list()?;
+
+    let next = self.lexer.peek().map_err(|err| err.into()).vec_err()?;
+
+    let else_branch = if next.token == Token::Else {
+      self.advance().vec_err()?;
+      self.parse_statement_list()?
+    } else {
+      Vec::new()
+    };
+
+    self.expect_eq(&Token::End).vec_err()?;
+    self.expect_eq(&Token::If).vec_err()?;
+    self.expect_eq(&Token::Semicolon).vec_err()?;
+
+    Ok(Statement::If {
+      condition,
+      then_branch,
+      else_branch,
+    })
+  }
+
+  fn parse_case_label(&mut self) -> Result<LiteralValue, ParserErrorWithCtx> {
+    let offset = self.lexer.offset();
+
+    // Negative numbers are folded into literals by the expression parser.
+    match self.parse_expression()? {
+      Expression::Literal(literal) => Ok(literal),
+      _ => Err(ParserError::InvalidCaseLabel).with_ctx(offset),
+    }
+  }
+
+  fn parse_case_arm(&mut self) -> Result<CaseArm, ParserErrors> {
+    let mut labels = vec![self.parse_case_label().vec_err()?];
+
+    while self.accept(&Token::Comma).vec_err()? {
+      labels.push(self.parse_case_label().vec_err()?);
+    }
+
+    self.expect_eq(&Token::Colon).vec_err()?;
+
+    // The body ends at the label of the next arm. Statements never start with a literal or a minus.
+    let body = self.parse_statement_list_until(|token| {
+      matches!(
+        *token,
+        Token::EndOfFile
+          | Token::End
+          | Token::Else
+          | Token::Literal(_)
+          | Token::Operator(Operator::BinaryOperator(BinaryOperator::Sub))
+      )
+    })?;
+
+    Ok(CaseArm { labels, body })
+  }
+
+  pub fn parse_case(&mut self) -> Result<Statement, ParserErrors> {
+    self.expect_eq(&Token::Case).vec_err()?;
+
+    let scrutinee = self.parse_expression().vec_err()?;
+
+    self.expect_eq(&Token::Of).vec_err()?;
+
+    let mut arms = Vec::new();
+
+    loop {
+      match self.lexer.peek().map_err(|err| err.into()).vec_err()?.token {
+        Token::End | Token::Else => break,
+        _ => arms.push(self.parse_case_arm()?),
+      }
+    }
+
+    let default = if self.accept(&Token::Else).vec_err()? {
+      self.parse_statement_list()?
+    } else {
+      Vec::new()
+    };
+
+    self.expect_eq(&Token::End).vec_err()?;
+    self.expect_eq(&Token::Case).vec_err()?;
+    self.expect_eq(&Token::Semicolon).vec_err()?;
+
+    Ok(Statement::Case {
+      scrutinee,
+      arms,
+      default,
+    })
+  }
+
+  pub fn parse_while(&mut self) -> Result<Statement, ParserErrors> {
+    self.expect_eq(&Token::While).vec_err()?;
+
+    let condition = self.parse_expression().vec_err()?;
+
+    self.expect_eq(&Token::Do).vec_err()?;
+
+    let run = self.parse_statement_list()?;
+
+    self.expect_eq(&Token::End).vec_err()?;
+    self.expect_eq(&Token::While).vec_err()?;
+    self.expect_eq(&Token::Semicolon).vec_err()?;
+
+    Ok(Statement::While { condition, run })
+  }
+
+  fn parse_loop_control(&mut self) -> Result<Statement, ParserErrorWithCtx> {
+    let statement = match self.lexer.next()?.token {
+      Token::Break => Statement::Break,
+      _ => Statement::Continue,
+    };
+
+    self.expect_eq(&Token::Semicolon)?;
+
+    Ok(statement)
+  }
+
+  fn parse_parameter(&mut self) -> Result<Parameter, ParserErrorWithCtx> {
+    let name = self.expect_identifier()?;
+    self.expect_eq(&Token::Colon)?;
+    let type_of = self.expect_type_name()?;
+    Ok(Parameter { name, type_of })
+  }
+
+  // Parses both procedures and functions, which only differ by the return type.
+  pub fn parse_function(&mut self) -> Result<Statement, ParserErrors> {
+    let keyword = self.lexer.next().map_err(|err| err.into()).vec_err()?.token;
+
+    let name = self.expect_identifier().vec_err()?;
+    let parameters = self
+      .parse_parenthesised_list(|parser| parser.parse_parameter())
+      .vec_err()?;
+
+    let return_type = if keyword == Token::Function {
+      self.expect_eq(&Token::Colon).vec_err()?;
+      Some(self.expect_type_name().vec_err()?)
+    } else {
+      None
+    };
+
+    self.expect_eq(&Token::Do).vec_err()?;
+
+    let body = self.parse_statement_list()?;
+
+    self.expect_eq(&Token::End).vec_err()?;
+    self.expect_eq(&keyword).vec_err()?;
+    self.expect_eq(&Token::Semicolon).vec_err()?;
+
+    Ok(Statement::Function(Function {
+      name,
+      parameters,
+      return_type,
+      body,
+    }))
+  }
+
+  fn parse_record_definition(&mut self) -> Result<UserType, ParserErrorWithCtx> {
+    self.expect_eq(&Token::Record)?;
+
+    let mut fields = Vec::new();
+
+    while self.lexer.peek()?.token != Token::End {
+      let field = self.expect_identifier()?;
+      self.expect_eq(&Token::Colon)?;
+      let type_of = self.expect_type_name()?;
+      self.expect_eq(&Token::Semicolon)?;
+      fields.push((field, type_of));
+    }
+
+    self.expect_eq(&Token::End)?;
+    self.expect_eq(&Token::Record)?;
+
+    Ok(UserType::Record(fields))
+  }
+
+  fn parse_type_definition(&mut self) -> Result<Statement, ParserErrorWithCtx> {
+    self.expect_eq(&Token::TypeDef)?;
+
+    let name = self.expect_identifier()?;
+
+    self.expect_eq(&Token::Operator(Operator::BinaryOperator(
+      BinaryOperator::Equal,
+    )))?;
+
+    // Enums list their members in parentheses.
+    let definition = if self.lexer.peek()?.token == Token::LParen {
+      UserType::Enum(self.parse_parenthesised_list(|parser| parser.expect_identifier())?)
+    } else {
+      self.parse_record_definition()?
+    };
+
+    self.expect_eq(&Token::Semicolon)?;
+
+    Ok(Statement::TypeDefinition { name, definition })
+  }
+
+  fn parse_return(&mut self) -> Result<Statement, ParserErrorWithCtx> {
+    self.expect_eq(&Token::Return)?;
+
+    let value = if self.lexer.peek()?.token == Token::Semicolon {
+      None
+    } else {
+      Some(self.parse_expression()?)
+    };
+
+    self.expect_eq(&Token::Semicolon)?;
+
+    Ok(Statement::Return(value))
+  }
+
   pub fn parse_statement(&mut self) -> Result<Statement, ParserErrors> {
     let first = self.lexer.peek().map_err(|err| err.into()).vec_err()?;
     match &first.token {
-      &Token::Print => self.parse_print_statement().vec_err(),
+      &Token::Print | &Token::PrintLn => self.parse_print_statement().vec_err(),
       &Token::Read => self.parse_read_statement().vec_err(),
-      &Token::Var => self.parse_decleration().vec_err(),
+      &Token::Var | &Token::Const => self.parse_decleration().vec_err(),
       &Token::Assert => self.parse_assertion().vec_err(),
-      &Token::Identifier(_) => self.parse_assignment().vec_err(),
+      &Token::Identifier(_) => self.parse_assignment_or_call().vec_err(),
       &Token::For => self.parse_for(),
+      &Token::If => self.parse_if(),
+      &Token::Case => self.parse_case(),
+      &Token::While => self.parse_while(),
+      &Token::Break | &Token::Continue => self.parse_loop_control().vec_err(),
+      &Token::Procedure | &Token::Function => self.parse_function(),
+      &Token::Return => self.parse_return().vec_err(),
+      &Token::TypeDef => self.parse_type_definition().vec_err(),
       other => Err(ParserError::UnknownStatement {
         first: other.get_kind(),
       }).with_ctx(first.offset)
@@ -309,12 +830,25 @@ impl<T: TokenStream> Parser<T> {
   }
 
   pub fn parse_statement_list(&mut self) -> Result<Vec<StatementWithCtx>, ParserErrors> {
+    // If we reached end of file OR a keyword ending the block, stop parsing.
+    self.parse_statement_list_until(|token| {
+      matches!(*token, Token::EndOfFile | Token::End | Token::Else)
+    })
+  }
+
+  // Parses statements until the next token satisfies `is_end`.
+  fn parse_statement_list_until<F>(
+    &mut self,
+    is_end: F,
+  ) -> Result<Vec<StatementWithCtx>, ParserErrors>
+  where
+    F: Fn(&Token) -> bool,
+  {
     let mut statements = Vec::new();
 
     loop {
       let next = self.lexer.peek().map_err(|err| err.into()).vec_err()?;
-      // If we reached end of file OR the end keyword, stop parsing.
-      if next.token == Token::EndOfFile || next.token == Token::End {
+      if is_end(&next.token) {
         break;
       }
 
@@ -340,6 +874,40 @@ impl<T: TokenStream> Parser<T> {
     Ok(statements)
   }
 
+  fn parse_import(&mut self) -> Result<Import, ParserErrorWithCtx> {
+    let start = self.lexer.offset();
+    self.expect_eq(&Token::Import)?;
+
+    let next = self.lexer.next()?;
+    let path = match next.token {
+      Token::Literal(LiteralValue::StringLiteral(path)) => path,
+      other => {
+        return Err(ParserError::UnexpectedToken {
+          expected: TokenKind::LiteralK,
+          was: other.get_kind(),
+        }).with_ctx(next.offset)
+      }
+    };
+
+    self.expect_eq(&Token::Semicolon)?;
+
+    Ok(Import {
+      path,
+      source_position: start..self.lexer.offset(),
+    })
+  }
+
+  /// Parses the imports at the start of a program. Imports have to precede all statements.
+  pub fn parse_imports(&mut self) -> Result<Vec<Import>, ParserErrors> {
+    let mut imports = Vec::new();
+
+    while let Token::Import = self.lexer.peek().map_err(|err| err.into()).vec_err()?.token {
+      imports.push(self.parse_import().vec_err()?);
+    }
+
+    Ok(imports)
+  }
+
   pub fn parse_program(&mut self) -> Result<Vec<StatementWithCtx>, ParserErrors> {
     let mut errors = Vec::new();
 
@@ -399,7 +967,11 @@ impl<T: TokenStream> TryRecover for Parser<T> {
 
 #[cfg(test)]
 mod tests {
+  use common::errors::LexerError::IntegerLiteralOverflow;
   use common::errors::ParserError::*;
+  use common::types::{BinaryOperator, LiteralValue, UnaryOperator};
+  use parsing::ast::Expression::*;
+  use parsing::ast::Statement;
   use parsing::parser_test_util::*;
   use parsing::token::TokenKind::*;
 
@@ -409,6 +981,155 @@ mod tests {
     assert_match!(result => Err(IncompleteExpression));
   }
 
+  #[test]
+  fn parse_call_expression() {
+    let result = parse_expr("f(1, g(2) * 3) + 4").expect("Should parse.");
+
+    match result {
+      BinaryOp(_, args) => match args.0 {
+        Call(ref name, ref arguments) => {
+          assert_eq!(name, "f");
+          assert_eq!(arguments.len(), 2);
+        }
+        other => panic!("Expected a call, was {:?}", other),
+      },
+      other => panic!("Expected a binary expression, was {:?}", other),
+    }
+  }
+
+  #[test]
+  fn postfix_access_after_any_operand() {
+    match parse_expr("\"abc\"[1]").expect("Should parse.") {
+      Index(params) => assert_match!(params.0 => Literal(LiteralValue::StringLiteral(_))),
+      other => panic!("Expected an index, was {:?}", other),
+    }
+
+    match parse_expr("(s + t)[0..1]").expect("Should parse.") {
+      Slice(params) => assert_match!(params.0 => BinaryOp(BinaryOperator::Add, _)),
+      other => panic!("Expected a slice, was {:?}", other),
+    }
+
+    match parse_expr("f(x)[0].y").expect("Should parse.") {
+      Field(inner, _) => match *inner {
+        Index(params) => assert_match!(params.0 => Call(..)),
+        other => panic!("Expected an index, was {:?}", other),
+      },
+      other => panic!("Expected a field access, was {:?}", other),
+    }
+
+    // Indexing binds tighter than the operators around the parenthesised operand.
+    match parse_expr("-(s)[0] * 2").expect("Should parse.") {
+      BinaryOp(BinaryOperator::Mul, args) => match args.0 {
+        UnaryOp(UnaryOperator::Negate, inner) => assert_match!(*inner => Index(_)),
+        other => panic!("Expected a negation, was {:?}", other),
+      },
+      other => panic!("Expected a multiplication, was {:?}", other),
+    }
+  }
+
+  #[test]
+  fn binary_operators_are_left_associative() {
+    let result = parse_expr("1 * 2 % 3").expect("Should parse.");
+
+    match result {
+      BinaryOp(BinaryOperator::Rem, args) => {
+        assert_match!(args.0 => BinaryOp(BinaryOperator::Mul, _));
+      }
+      other => panic!("Expected a remainder expression, was {:?}", other),
+    }
+  }
+
+  #[test]
+  fn prefix_minus() {
+    let result = parse_expr("-x - -(1)").expect("Should parse.");
+
+    match result {
+      BinaryOp(BinaryOperator::Sub, args) => {
+        assert_match!(args.0 => UnaryOp(UnaryOperator::Negate, _));
+        assert_match!(args.1 => UnaryOp(UnaryOperator::Negate, _));
+      }
+      other => panic!("Expected a subtraction, was {:?}", other),
+    }
+  }
+
+  #[test]
+  fn negative_literals() {
+    let result = parse_expr("-2147483648").expect("Should parse.");
+    assert_match!(result => Literal(LiteralValue::IntLiteral(-2147483648)));
+  }
+
+  #[test]
+  fn int_literal_out_of_range() {
+    let result = parse_expr("2147483648");
+    assert_match!(result => Err(LexerError(_)));
+  }
+
+  #[test]
+  fn min_int_magnitude_keeps_the_literal_as_written() {
+    for &literal in &["0x8000_0000", "2_147_483_648", "0o20_000_000_000"] {
+      match parse_expr(literal) {
+        Err(LexerError(IntegerLiteralOverflow { literal: written, is_negated, excess })) => {
+          assert_eq!((written.as_str(), is_negated, excess), (literal, false, Some(1)));
+        }
+        result => panic!("Expected an overflow, was {:?}", result),
+      }
+
+      let result = parse_expr(&format!("-{}", literal)).expect("Should parse.");
+      assert_match!(result => Literal(LiteralValue::IntLiteral(-2147483648)));
+    }
+
+    // The literal is subtracted, so it must be positive.
+    let result = parse_expr("1 - 0x8000_0000");
+    assert_match!(result => Err(LexerError(IntegerLiteralOverflow { is_negated: false, .. })));
+  }
+
+  #[test]
+  fn negated_int_literal_overflow_is_measured_from_min() {
+    let result = parse_expr("-2147483649");
+    assert_match!(result => Err(LexerError(IntegerLiteralOverflow {
+      is_negated: true,
+      excess: Some(1),
+      ..
+    })));
+
+    // This is a subtraction, so the literal itself is positive.
+    let result = parse_expr("5 -2147483649");
+    assert_match!(result => Err(LexerError(IntegerLiteralOverflow {
+      is_negated: false,
+      excess: Some(2),
+      ..
+    })));
+  }
+
+  #[test]
+  fn declaration_without_type() {
+    let result = parse_stmnt("var x := 1 + 2;").expect("Should parse.");
+    assert_match!(result => Statement::Declare { type_of: None, initial: Some(_), .. });
+  }
+
+  #[test]
+  fn doc_comments_are_attached_to_declarations() {
+    let result = parse_stmnt("/// The answer.\n/// Or so.\nconst x := 42;").expect("Should parse.");
+
+    match result {
+      Statement::Declare { doc, .. } => assert_eq!(doc, Some("The answer.\nOr so.".to_string())),
+      other => panic!("Expected a declaration, was {:?}", other),
+    }
+  }
+
+  #[test]
+  fn doc_comments_only_document_the_next_statement() {
+    let mut parser = create_parser("/// Not a declaration.\nprint 1;\nvar x := 1;");
+    let program = parser.parse_program().expect("Should parse.");
+    assert_match!(program[1].statement => Statement::Declare { doc: None, .. });
+  }
+
+  #[test]
+  fn declaration_without_type_or_value() {
+    let result = parse_stmnt("var x;");
+    assert_match!(result => Err(UnexpectedToken { expected: AssignK, was: SemicolonK }));
+  }
+
   #[test]
   fn reserved_keyword_var() {
     let result = parse_stmnt("var var : int := 10;");
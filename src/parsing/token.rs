@@ -4,25 +4,60 @@ use common::types::*;
 pub enum Token {
   Identifier(String),
   Literal(LiteralValue),
+  // The magnitude of i32::MIN, which is only a valid literal after a prefix minus. The literal is
+  // kept as written for error messages.
+  MinIntMagnitude(String),
+  // A string literal containing interpolated expressions.
+  InterpolatedString(Vec<StringPart>),
   Type(TypeName),
   Operator(Operator),
   Semicolon,
   LParen,
   RParen,
+  LBracket,
+  RBracket,
   Colon,
+  Comma,
+  Dot,
   Assign,
   Print,
+  PrintLn,
   Read,
   Var,
+  Const,
   Assert,
   For,
   In,
   Range,
+  DownTo,
+  Step,
   Do,
   End,
+  If,
+  Then,
+  Case,
+  Else,
+  While,
+  Break,
+  Continue,
+  Procedure,
+  Function,
+  Return,
+  Array,
+  Of,
+  TypeDef,
+  Record,
+  Import,
   EndOfFile,
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum StringPart {
+  Text(String),
+  // The tokens of an interpolated expression, ending in EndOfFile at the closing brace.
+  Code(Vec<TokenWithCtx>),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct TokenWithCtx {
   pub offset: usize,
@@ -33,22 +68,46 @@ pub struct TokenWithCtx {
 pub enum TokenKind {
   IdentifierK,
   LiteralK,
+  InterpolatedStringK,
   TypeK,
   OperatorK,
   SemicolonK,
   LParenK,
   RParenK,
+  LBracketK,
+  RBracketK,
   ColonK,
+  CommaK,
+  DotK,
   AssignK,
   PrintK,
+  PrintLnK,
   ReadK,
   VarK,
+  ConstK,
   AssertK,
   ForK,
   InK,
   RangeK,
+  DownToK,
+  StepK,
   DoK,
   EndK,
+  IfK,
+  ThenK,
+  CaseK,
+  ElseK,
+  WhileK,
+  BreakK,
+  ContinueK,
+  ProcedureK,
+  FunctionK,
+  ReturnK,
+  ArrayK,
+  OfK,
+  TypeDefK,
+  RecordK,
+  ImportK,
   EndOfFileK,
 }
 
@@ -56,23 +115,47 @@ impl Token {
   pub fn get_kind(&self) -> TokenKind {
     match *self {
       Token::Identifier(_) => TokenKind::IdentifierK,
-      Token::Literal(_) => TokenKind::LiteralK,
+      Token::Literal(_) | Token::MinIntMagnitude(_) => TokenKind::LiteralK,
+      Token::InterpolatedString(_) => TokenKind::InterpolatedStringK,
       Token::Type(_) => TokenKind::TypeK,
       Token::Operator(_) => TokenKind::OperatorK,
       Token::Semicolon => TokenKind::SemicolonK,
       Token::Colon => TokenKind::ColonK,
+      Token::Comma => TokenKind::CommaK,
+      Token::Dot => TokenKind::DotK,
       Token::LParen => TokenKind::LParenK,
       Token::RParen => TokenKind::RParenK,
+      Token::LBracket => TokenKind::LBracketK,
+      Token::RBracket => TokenKind::RBracketK,
       Token::Assign => TokenKind::AssignK,
       Token::Print => TokenKind::PrintK,
+      Token::PrintLn => TokenKind::PrintLnK,
       Token::Read => TokenKind::ReadK,
       Token::Var => TokenKind::VarK,
+      Token::Const => TokenKind::ConstK,
       Token::Assert => TokenKind::AssertK,
       Token::For => TokenKind::ForK,
       Token::In => TokenKind::InK,
       Token::Range => TokenKind::RangeK,
+      Token::DownTo => TokenKind::DownToK,
+      Token::Step => TokenKind::StepK,
       Token::Do => TokenKind::DoK,
       Token::End => TokenKind::EndK,
+      Token::If => TokenKind::IfK,
+      Token::Then => TokenKind::ThenK,
+      Token::Case => TokenKind::CaseK,
+      Token::Else => TokenKind::ElseK,
+      Token::While => TokenKind::WhileK,
+      Token::Break => TokenKind::BreakK,
+      Token::Continue => TokenKind::ContinueK,
+      Token::Procedure => TokenKind::ProcedureK,
+      Token::Function => TokenKind::FunctionK,
+      Token::Return => TokenKind::ReturnK,
+      Token::Array => TokenKind::ArrayK,
+      Token::Of => TokenKind::OfK,
+      Token::TypeDef => TokenKind::TypeDefK,
+      Token::Record => TokenKind::RecordK,
+      Token::Import => TokenKind::ImportK,
       Token::EndOfFile => TokenKind::EndOfFileK,
     }
   }
@@ -1,3 +1,5 @@
+use std::cmp::min;
+
 use common::errors::*;
 use parsing::token::*;
 
@@ -15,6 +17,53 @@ pub trait TokenStream {
   /// Tries to read the next token.
   fn peek(&mut self) -> Result<TokenWithCtx, ErrWithCtx<LexerError>>;
 
+  /// Returns the documentation comment before the token returned by `peek()`, if any.
+  fn doc_comment(&self) -> Option<String>;
+
   /// Tries to read the next token, and advances to the next token on success.
   fn next(&mut self) -> Result<TokenWithCtx, ErrWithCtx<LexerError>>;
 }
+
+/// A token stream over tokens which have already been lexed. The last token must be EndOfFile.
+pub struct TokenBuffer {
+  tokens: Vec<TokenWithCtx>,
+  position: usize,
+}
+
+impl TokenBuffer {
+  pub fn new(tokens: Vec<TokenWithCtx>) -> TokenBuffer {
+    TokenBuffer {
+      tokens,
+      position: 0,
+    }
+  }
+}
+
+impl TokenStream for TokenBuffer {
+  fn advance(&mut self) {
+    self.position = min(self.position + 1, self.tokens.len() - 1);
+  }
+
+  fn reached_end(&self) -> bool {
+    self.position == self.tokens.len() - 1
+  }
+
+  fn offset(&self) -> usize {
+    self.tokens[self.position].offset
+  }
+
+  fn peek(&mut self) -> Result<TokenWithCtx, ErrWithCtx<LexerError>> {
+    Ok(self.tokens[self.position].clone())
+  }
+
+  // Documentation comments are only kept by the lexer.
+  fn doc_comment(&self) -> Option<String> {
+    None
+  }
+
+  fn next(&mut self) -> Result<TokenWithCtx, ErrWithCtx<LexerError>> {
+    let token = self.peek()?;
+    self.advance();
+    Ok(token)
+  }
+}
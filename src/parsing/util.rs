@@ -1,27 +1,22 @@
+use unicode_xid::UnicodeXID;
+
 pub fn is_whitespace(ch: char) -> bool {
-  match ch {
-    ' ' | '\r' | '\n' | '\t' => true,
-    _ => false,
-  }
+  matches!(ch, ' ' | '\r' | '\n' | '\t')
 }
 
 pub fn is_number(ch: char) -> bool {
-  match ch {
-    '0'...'9' => true,
-    _ => false,
-  }
+  ch.is_ascii_digit()
 }
 
-// No Unicode support for now.
-pub fn is_letter(ch: char) -> bool {
-  match ch {
-    'A'...'z' => true,
-    _ => false,
-  }
+// Identifiers follow the Unicode XID properties (UAX #31). XID_Start contains letters in any
+// script, but not digits or underscores.
+pub fn is_identifier_start(ch: char) -> bool {
+  ch.is_xid_start()
 }
 
+// XID_Continue adds digits, underscores and combining marks to XID_Start.
 pub fn is_valid_in_identifier(c: char) -> bool {
-  is_letter(c) || is_number(c) || c == '_'
+  c.is_xid_continue()
 }
 
 // An identifier starts with a letter, followed by a mix of letters, numbers and underscores.
@@ -31,7 +26,7 @@ pub fn is_valid_identifier(s: &str) -> bool {
   }
 
   for (i, c) in s.char_indices() {
-    if i == 0 && !is_letter(c) {
+    if i == 0 && !is_identifier_start(c) {
       return false;
     }
 
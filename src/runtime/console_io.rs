@@ -9,13 +9,12 @@ impl Io for ConsoleIo {
   fn read_line(&mut self) -> String {
     let stdin = stdin();
     // Take exclusive lock on stdin, split it into lines and read the first line
-    let line = stdin
+    stdin
       .lock()
       .lines()
       .next()
       .expect("There should be a line of input.")
-      .expect("Reading should succeed.");
-    line
+      .expect("Reading should succeed.")
   }
 
   fn write(&mut self, s: &str) {
@@ -1,6 +1,10 @@
 use std::collections::HashMap;
+use std::mem;
+use std::ops::Range;
 
-use common::types::{TypeName, Value};
+use common::builtins::Builtins;
+use common::errors::{RuntimeError, RuntimeErrorWithCtx};
+use common::types::{TypeName, UserType, UserTypes, Value};
 
 use diagnostics::file_context::FileContextSource;
 
@@ -8,13 +12,74 @@ use parsing::ast::*;
 
 use runtime::io::Io;
 
+// Signals how the enclosing statement list should proceed after executing a statement.
+#[derive(Debug, PartialEq, Clone)]
+enum ControlFlow {
+  Next,
+  Break,
+  Continue,
+  // Procedures return without a value.
+  Return(Option<Value>),
+}
+
 struct Variable {
   type_of: TypeName,
   value: Value,
 }
 
+type Scope = HashMap<String, Variable>;
+
+// The innermost scope of a frame is last.
+type Frame = Vec<Scope>;
+
+type RuntimeResult<T> = Result<T, RuntimeErrorWithCtx>;
+
+// Deeper recursion reports an error instead of overflowing the native stack.
+const MAX_CALL_DEPTH: usize = 200;
+
+// Values are padded to at most this many characters, to either side.
+const MAX_PRINT_WIDTH: i32 = 1000;
+
+// A single step from a value into one of its parts.
+enum Accessor<'b> {
+  Index(i32),
+  Field(&'b str),
+}
+
+fn get_field<'b>(fields: &'b mut [(String, Value)], field: &str) -> &'b mut Value {
+  fields
+    .iter_mut()
+    .find(|(name, _)| name == field)
+    .map(|(_, value)| value)
+    .expect("Type checker will prevent the use of unknown fields.")
+}
+
+// The value which an element or field is read from.
+enum AccessBase<'b> {
+  Variable(&'b str),
+  // A temporary, such as the result of a call.
+  Value(Value),
+}
+
+fn check_bounds(index: i32, length: usize) -> Result<usize, RuntimeError> {
+  if index < 0 || index as usize >= length {
+    Err(RuntimeError::IndexOutOfBounds { index, length })
+  } else {
+    Ok(index as usize)
+  }
+}
+
 pub struct Interpreter<'a, T: Io + 'a> {
-  variables: HashMap<String, Variable>,
+  // Each subroutine call gets its own frame for parameters and local variables.
+  // The first frame belongs to the main program, and its first scope contains global variables.
+  frames: Vec<Frame>,
+  functions: HashMap<String, &'a Function>,
+  builtins: Builtins,
+  user_types: UserTypes,
+  // Source position of the statement being executed, used for error reporting.
+  position: Range<usize>,
+  // The part of the last input line which hasn't been read yet.
+  pending_input: String,
   ctx: &'a FileContextSource,
   io: &'a mut T,
 }
@@ -24,69 +89,409 @@ impl<'a, T: Io> Interpreter<'a, T> {
     Interpreter {
       io,
       ctx,
-      variables: HashMap::new(),
+      frames: vec![vec![HashMap::new()]],
+      functions: HashMap::new(),
+      builtins: Builtins::default(),
+      user_types: HashMap::new(),
+      position: 0..0,
+      pending_input: String::new(),
     }
   }
 
+  // Variables in inner scopes shadow variables in outer scopes, and local variables shadow
+  // global variables.
+  fn get_variable(&self, identifier: &str) -> &Variable {
+    let frame = self.frames.last().expect("There is always at least one frame.");
+
+    frame
+      .iter()
+      .rev()
+      .chain(&self.frames[0][..1])
+      .filter_map(|scope| scope.get(identifier))
+      .next()
+      .expect("Type checker will prevent the use of undeclared variables.")
+  }
+
+  fn get_variable_mut(&mut self, identifier: &str) -> &mut Variable {
+    let frame_index = self.frames.len() - 1;
+    let scope_index = self.frames[frame_index]
+      .iter()
+      .rposition(|scope| scope.contains_key(identifier));
+
+    let scope = match scope_index {
+      Some(scope_index) => &mut self.frames[frame_index][scope_index],
+      None => &mut self.frames[0][0],
+    };
+
+    scope
+      .get_mut(identifier)
+      .expect("Type checker will prevent the use of undeclared variables.")
+  }
+
+  fn current_frame(&mut self) -> &mut Frame {
+    self.frames.last_mut().expect("There is always at least one frame.")
+  }
+
   fn declare(&mut self, identifier: &str, type_of: TypeName, value: Value) {
-    self
-      .variables
-      .insert(identifier.to_string(), Variable { type_of, value });
+    let scope = self.current_frame().last_mut().expect("There is always at least one scope.");
+    scope.insert(identifier.to_string(), Variable { type_of, value });
   }
 
   fn assign(&mut self, identifier: &str, value: Value) {
-    let variable = self.variables.get_mut(identifier).unwrap();
-    variable.value = value;
+    self.get_variable_mut(identifier).value = value;
   }
 
-  fn evaluate_binary_expression(&self, params: &(Expression, Expression)) -> (Value, Value) {
-    let left = self.evaluate_expression(&params.0);
-    let right = self.evaluate_expression(&params.1);
-    (left, right)
+  fn get_enum_members(&self, name: &str) -> Vec<String> {
+    match self.user_types[name] {
+      UserType::Enum(ref members) => members.clone(),
+      _ => panic!("Type checker will prevent this."),
+    }
   }
 
-  fn evaluate_expression(&self, expression: &Expression) -> Value {
+  // Returns the next whitespace-separated word of input, reading more lines as needed.
+  fn read_word(&mut self) -> String {
+    while self.pending_input.trim().is_empty() {
+      self.pending_input = self.io.read_line();
+    }
+
+    let (word, rest) = {
+      let line = self.pending_input.trim_start();
+      let end = line.find(char::is_whitespace).unwrap_or(line.len());
+      (line[..end].to_string(), line[end..].to_string())
+    };
+
+    self.pending_input = rest;
+    word
+  }
+
+  // Strings may contain spaces, so they extend to the end of the line.
+  fn read_rest_of_line(&mut self) -> String {
+    let rest = mem::take(&mut self.pending_input);
+
+    if rest.trim().is_empty() {
+      self.io.read_line()
+    } else {
+      rest.trim_start().to_string()
+    }
+  }
+
+  fn read_value(&mut self, type_of: &TypeName) -> RuntimeResult<Value> {
+    if *type_of == TypeName::StringType {
+      return Ok(Value::StringV(self.read_rest_of_line()));
+    }
+
+    let word = self.read_word();
+
+    let value = match *type_of {
+      TypeName::IntType => str::parse(&word)
+        .map(Value::IntV)
+        .map_err(|_| RuntimeError::InvalidNumber(word)),
+      TypeName::RealType => str::parse(&word)
+        .map(Value::RealV)
+        .map_err(|_| RuntimeError::InvalidNumber(word)),
+      TypeName::BoolType => match word.as_str() {
+        "true" => Ok(Value::BoolV(true)),
+        "false" => Ok(Value::BoolV(false)),
+        _ => Err(RuntimeError::InvalidBool(word)),
+      },
+      _ => panic!("Type checker will handle this"),
+    };
+
+    value.map_err(|err| self.error(err))
+  }
+
+  fn error(&self, error: RuntimeError) -> RuntimeErrorWithCtx {
+    RuntimeErrorWithCtx(error, self.position.clone())
+  }
+
+  // Converts the result of checked integer arithmetic into a value, or an overflow error.
+  fn checked(&self, result: Option<i32>) -> RuntimeResult<Value> {
+    result
+      .map(Value::IntV)
+      .ok_or_else(|| self.error(RuntimeError::IntegerOverflow))
+  }
+
+  // Evaluates the indices of an assignment target from left to right, and returns the name of
+  // the variable being assigned to.
+  fn evaluate_lvalue_path<'b>(
+    &mut self,
+    target: &'b LValue,
+    path: &mut Vec<Accessor<'b>>,
+  ) -> RuntimeResult<&'b str> {
+    match *target {
+      LValue::Variable(ref name) => Ok(name),
+      LValue::Index(ref inner, ref index) => {
+        let name = self.evaluate_lvalue_path(inner, path)?;
+        match self.evaluate_expression(index)? {
+          Value::IntV(index) => path.push(Accessor::Index(index)),
+          _ => panic!("Type checker will prevent this."),
+        }
+        Ok(name)
+      }
+      LValue::Field(ref inner, ref field) => {
+        let name = self.evaluate_lvalue_path(inner, path)?;
+        path.push(Accessor::Field(field));
+        Ok(name)
+      }
+    }
+  }
+
+  // Evaluates the indices of an element or field access from left to right, and returns the
+  // value being accessed.
+  fn evaluate_access_path<'b>(
+    &mut self,
+    expression: &'b Expression,
+    path: &mut Vec<Accessor<'b>>,
+  ) -> RuntimeResult<AccessBase<'b>> {
+    match *expression {
+      Expression::Variable(ref name) => Ok(AccessBase::Variable(name)),
+      Expression::Index(ref params) => {
+        let base = self.evaluate_access_path(&params.0, path)?;
+        match self.evaluate_expression(&params.1)? {
+          Value::IntV(index) => path.push(Accessor::Index(index)),
+          _ => panic!("Type checker will prevent this."),
+        }
+        Ok(base)
+      }
+      Expression::Field(ref inner, ref field) => {
+        let base = self.evaluate_access_path(inner, path)?;
+        path.push(Accessor::Field(field));
+        Ok(base)
+      }
+      ref other => Ok(AccessBase::Value(self.evaluate_expression(other)?)),
+    }
+  }
+
+  // Follows the path through a borrow of the value, so that only the result is cloned.
+  fn read_path(&self, value: &Value, path: &[Accessor]) -> RuntimeResult<Value> {
+    let mut slot = value;
+
+    for accessor in path {
+      slot = match (slot, accessor) {
+        (Value::ArrayV(elements), &Accessor::Index(index)) => {
+          let index = check_bounds(index, elements.len()).map_err(|err| self.error(err))?;
+          &elements[index]
+        }
+        (Value::RecordV(_, fields), &Accessor::Field(field)) => fields
+          .iter()
+          .find(|(name, _)| name == field)
+          .map(|(_, value)| value)
+          .expect("Type checker will prevent the use of unknown fields."),
+        // Strings are indexed by character, not by byte. A character can't be accessed further.
+        (Value::StringV(s), &Accessor::Index(index)) => {
+          let length = s.chars().count();
+          let index = check_bounds(index, length).map_err(|err| self.error(err))?;
+          let character = s.chars().nth(index).expect("The index is in bounds.");
+          return Ok(Value::StringV(character.to_string()));
+        }
+        _ => panic!("Type checker will prevent this."),
+      };
+    }
+
+    Ok(slot.clone())
+  }
+
+  fn evaluate_access(&mut self, expression: &Expression) -> RuntimeResult<Value> {
+    let mut path = Vec::new();
+
+    match self.evaluate_access_path(expression, &mut path)? {
+      AccessBase::Variable(name) => self.read_path(&self.get_variable(name).value, &path),
+      AccessBase::Value(value) => self.read_path(&value, &path),
+    }
+  }
+
+  fn assign_lvalue(&mut self, target: &LValue, value: Value) -> RuntimeResult<()> {
+    let mut path = Vec::new();
+    let name = self.evaluate_lvalue_path(target, &mut path)?;
+
+    let position = self.position.clone();
+    let mut slot = &mut self.get_variable_mut(name).value;
+
+    for accessor in path {
+      slot = match (slot, accessor) {
+        (&mut Value::ArrayV(ref mut elements), Accessor::Index(index)) => {
+          let index = check_bounds(index, elements.len())
+            .map_err(|err| RuntimeErrorWithCtx(err, position.clone()))?;
+          &mut elements[index]
+        }
+        (&mut Value::RecordV(_, ref mut fields), Accessor::Field(field)) => {
+          get_field(fields, field)
+        }
+        _ => panic!("Type checker will prevent this."),
+      };
+    }
+
+    *slot = value;
+    Ok(())
+  }
+
+  fn call(&mut self, name: &str, arguments: &[Expression]) -> RuntimeResult<Option<Value>> {
+    // The type checker prevents user-defined subroutines from shadowing builtins.
+    if let Some(implementation) = self.builtins.get(name).map(|builtin| builtin.implementation) {
+      let arguments = arguments
+        .iter()
+        .map(|argument| self.evaluate_expression(argument))
+        .collect::<RuntimeResult<Vec<Value>>>()?;
+
+      return implementation(&arguments)
+        .map(Some)
+        .map_err(|err| self.error(err));
+    }
+
+    let function = *self
+      .functions
+      .get(name)
+      .expect("Type checker will prevent calls to undeclared subroutines.");
+
+    let mut parameters = HashMap::new();
+
+    // Arguments are evaluated in the caller's frame.
+    for (parameter, argument) in function.parameters.iter().zip(arguments) {
+      let variable = Variable {
+        type_of: parameter.type_of.clone(),
+        value: self.evaluate_expression(argument)?,
+      };
+      parameters.insert(parameter.name.to_string(), variable);
+    }
+
+    // Besides the main program, every active call has a frame.
+    if self.frames.len() > MAX_CALL_DEPTH {
+      return Err(self.error(RuntimeError::StackOverflow(MAX_CALL_DEPTH)));
+    }
+
+    // Parameters and local variables share the same scope.
+    self.frames.push(vec![parameters]);
+    let result = self.execute_statements(&function.body)?;
+    self.frames.pop();
+
+    match result {
+      ControlFlow::Return(value) => Ok(value),
+      _ => Ok(None),
+    }
+  }
+
+  fn evaluate_binary_expression(
+    &mut self,
+    params: &(Expression, Expression),
+  ) -> RuntimeResult<(Value, Value)> {
+    let left = self.evaluate_expression(&params.0)?;
+    let right = self.evaluate_expression(&params.1)?;
+    Ok((left, right))
+  }
+
+  fn evaluate_expression(&mut self, expression: &Expression) -> RuntimeResult<Value> {
     use common::types::BinaryOperator::*;
     use common::types::UnaryOperator::*;
     use common::types::Value::*;
     use parsing::ast::Expression::*;
 
-    match *expression {
+    let value = match *expression {
       // `Into` casts the literal value into a runtime value
       Literal(ref value) => value.clone().into(),
-      Variable(ref variable) => {
-        let var = *self
-          .variables
-          .get(variable)
-          .as_ref()
-          .expect("Type checker will prevent the use of undeclared variables.");
-        var.value.clone()
+      Variable(ref variable) => self.get_variable(variable).value.clone(),
+      Call(ref name, ref arguments) => self
+        .call(name, arguments)?
+        .expect("Type checker will prevent the use of procedures in expressions."),
+      Index(..) | Field(..) => self.evaluate_access(expression)?,
+      Slice(ref params) => {
+        let (ref target, ref start, ref end) = **params;
+        let target = self.evaluate_expression(target)?;
+        let start = self.evaluate_expression(start)?;
+        let end = self.evaluate_expression(end)?;
+
+        match (target, start, end) {
+          (StringV(s), IntV(start), IntV(end)) => {
+            let length = s.chars().count();
+
+            // The slice is empty when the end is just before the start.
+            if start < 0 || end < start - 1 || i64::from(end) >= length as i64 {
+              return Err(self.error(RuntimeError::InvalidSlice { start, end, length }));
+            }
+
+            StringV(
+              s.chars()
+                .skip(start as usize)
+                .take((end + 1 - start) as usize)
+                .collect(),
+            )
+          }
+          _ => panic!("Type checker will prevent this."),
+        }
       }
+      Interpolation(ref parts) => {
+        let mut result = String::new();
+
+        for part in parts {
+          match *part {
+            InterpolationPart::Text(ref text) => result.push_str(text),
+            InterpolationPart::Value(ref value) => {
+              result.push_str(&self.evaluate_expression(value)?.to_string())
+            }
+          }
+        }
+
+        StringV(result)
+      }
+      Convert(ref to, ref inner) => match (to, self.evaluate_expression(inner)?) {
+        (&TypeName::RealType, IntV(i)) => RealV(f64::from(i)),
+        // Reals are truncated towards zero.
+        (&TypeName::IntType, RealV(r)) => {
+          let truncated = r.trunc();
+          // NaN fails both comparisons.
+          if truncated >= f64::from(i32::MIN) && truncated <= f64::from(i32::MAX) {
+            IntV(truncated as i32)
+          } else {
+            return Err(self.error(RuntimeError::IntegerOverflow));
+          }
+        }
+        (_, value) => value,
+      },
       BinaryOp(ref op, ref params) => {
-        let (left, right) = self.evaluate_binary_expression(params);
+        let (left, right) = self.evaluate_binary_expression(params)?;
         match (*op, left, right) {
-          (Add, IntV(a), IntV(b)) => IntV(a + b),
+          (Add, IntV(a), IntV(b)) => self.checked(a.checked_add(b))?,
           (Add, StringV(a), StringV(b)) => StringV(a + &b),
-          (Sub, IntV(a), IntV(b)) => IntV(a - b),
-          (Mul, IntV(a), IntV(b)) => IntV(a * b),
-          (Div, IntV(a), IntV(b)) => IntV(a / b),
+          (Sub, IntV(a), IntV(b)) => self.checked(a.checked_sub(b))?,
+          (Mul, IntV(a), IntV(b)) => self.checked(a.checked_mul(b))?,
+          (Div, IntV(_), IntV(0)) | (Rem, IntV(_), IntV(0)) => {
+            return Err(self.error(RuntimeError::DivisionByZero))
+          }
+          // Overflow is only possible for i32::MIN / -1.
+          (Div, IntV(a), IntV(b)) => self.checked(a.checked_div(b))?,
+          // Wrapping is only possible for i32::MIN % -1, where the result is 0 anyway.
+          (Rem, IntV(a), IntV(b)) => IntV(a.wrapping_rem(b)),
+          (Add, RealV(a), RealV(b)) => RealV(a + b),
+          (Sub, RealV(a), RealV(b)) => RealV(a - b),
+          (Mul, RealV(a), RealV(b)) => RealV(a * b),
+          (Div, RealV(a), RealV(b)) => RealV(a / b),
           (Equal, a, b) => BoolV(a == b),
+          (NotEqual, a, b) => BoolV(a != b),
           (LessThan, a, b) => BoolV(a < b),
+          (LessThanOrEqual, a, b) => BoolV(a <= b),
+          (GreaterThan, a, b) => BoolV(a > b),
+          (GreaterThanOrEqual, a, b) => BoolV(a >= b),
           (And, BoolV(a), BoolV(b)) => BoolV(a && b),
+          (Or, BoolV(a), BoolV(b)) => BoolV(a || b),
           _ => panic!("Type checker will prevent this."),
         }
       }
       UnaryOp(ref op, ref param) => {
-        let inner = self.evaluate_expression(param);
+        let inner = self.evaluate_expression(param)?;
         match (*op, inner) {
           (Not, BoolV(x)) => BoolV(!x),
+          (Negate, IntV(x)) => self.checked(x.checked_neg())?,
+          (Negate, RealV(x)) => RealV(-x),
           _ => panic!("Type checker will prevent this."),
         }
       }
-    }
+    };
+
+    Ok(value)
   }
 
-  fn execute_statement(&mut self, statement: &StatementWithCtx) {
+  fn execute_statement(&mut self, statement: &'a StatementWithCtx) -> RuntimeResult<ControlFlow> {
+    self.position = statement.source_position.clone();
+
     match statement.statement {
       // We trust the type checker, so we don't have to check the type at runtime.
       Statement::Declare {
@@ -95,44 +500,71 @@ impl<'a, T: Io> Interpreter<'a, T> {
         ref initial,
         ..
       } => {
-        let initial_value = initial
-          .as_ref()
-          .map(|expr| self.evaluate_expression(expr))
-          .unwrap_or_else(|| type_of.get_default_value());
-        self.declare(name, *type_of, initial_value);
-      }
-      Statement::Assign(ref name, ref value) => {
-        let value = self.evaluate_expression(value);
-        self.assign(name, value);
+        let type_of = type_of
+          .clone()
+          .expect("Type checker will infer the types of declarations.");
+        let initial_value = match *initial {
+          Some(ref expr) => self.evaluate_expression(expr)?,
+          None => type_of.get_default_value(&self.user_types),
+        };
+        self.declare(name, type_of, initial_value);
       }
-      Statement::Print(ref expr) => {
-        let value = self.evaluate_expression(expr);
-        self.io.write(&value.to_string());
+      Statement::Assign(ref target, ref value) => {
+        let value = self.evaluate_expression(value)?;
+        self.assign_lvalue(target, value)?;
       }
-      Statement::Read(ref name) => {
-        let str_value = self.io.read_line();
+      Statement::Print {
+        ref arguments,
+        newline,
+      } => {
+        let mut output = String::new();
+
+        for argument in arguments {
+          let value = self.evaluate_expression(&argument.value)?.to_string();
 
-        match self.variables[name] {
-          Variable {
-            type_of: TypeName::StringType,
-            ..
-          } => {
-            self.assign(name, Value::StringV(str_value));
+          let width = match argument.width {
+            Some(ref width) => match self.evaluate_expression(width)? {
+              Value::IntV(width) => width,
+              _ => panic!("Type checker will prevent this."),
+            },
+            None => 0,
+          };
+
+          if width.unsigned_abs() > MAX_PRINT_WIDTH.unsigned_abs() {
+            return Err(self.error(RuntimeError::InvalidWidth {
+              width,
+              max: MAX_PRINT_WIDTH,
+            }));
           }
-          Variable {
-            type_of: TypeName::IntType,
-            ..
-          } => {
-            let as_int = str::parse(&str_value).unwrap();
-            self.assign(name, Value::IntV(as_int));
+
+          // Padding is counted in characters.
+          let padding = (width.unsigned_abs() as usize).saturating_sub(value.chars().count());
+          let spaces = " ".repeat(padding);
+
+          if width < 0 {
+            output.push_str(&value);
+            output.push_str(&spaces);
+          } else {
+            output.push_str(&spaces);
+            output.push_str(&value);
           }
-          _ => panic!("Type checker will handle this"),
         }
+
+        if newline {
+          output.push('\n');
+        }
+
+        self.io.write(&output);
       }
+      Statement::Read(ref names) => for name in names {
+        let type_of = self.get_variable(name).type_of.clone();
+        let value = self.read_value(&type_of)?;
+        self.assign(name, value);
+      },
       Statement::Assert(ref expr) => {
-        let value = self.evaluate_expression(expr);
+        let value = self.evaluate_expression(expr)?;
         match value {
-          Value::BoolV(true) => return,
+          Value::BoolV(true) => {}
           Value::BoolV(false) => {
             let source_quote = self.ctx.get_source_quote(&statement.source_position);
             self
@@ -146,28 +578,171 @@ impl<'a, T: Io> Interpreter<'a, T> {
         ref variable,
         ref from,
         ref to,
+        ref step,
+        is_descending,
         ref run,
       } => {
-        let from_value = self.evaluate_expression(from);
-        let to_value = self.evaluate_expression(to);
+        let from_value = self.evaluate_expression(from)?;
+        let to_value = self.evaluate_expression(to)?;
 
-        match (from_value, to_value) {
-          (Value::IntV(from), Value::IntV(to)) => for i in from..(to + 1) {
-            self.assign(variable, Value::IntV(i));
-
-            for statement in run {
-              self.execute_statement(statement);
-            }
+        let step = match *step {
+          Some(ref step) => match self.evaluate_expression(step)? {
+            Value::IntV(0) => return Err(self.error(RuntimeError::ZeroStep)),
+            Value::IntV(step) => i64::from(step),
+            _ => panic!("Type checker will prevent this"),
           },
+          None => 1,
+        };
+
+        // The sign of the step doesn't matter with downto, which always counts down.
+        let step = if is_descending { -step.abs() } else { step };
+
+        // Enum ranges iterate over the members by their index.
+        let (from, to, enum_type) = match (from_value, to_value) {
+          (Value::IntV(from), Value::IntV(to)) => (from, to, None),
+          (Value::EnumV(name, from, _), Value::EnumV(_, to, _)) => {
+            let members = self.get_enum_members(&name);
+            (from as i32, to as i32, Some((name, members)))
+          }
           _ => panic!("Type checker will prevent this"),
+        };
+
+        // The counter is wider than the bounds, so stepping past them can't overflow.
+        let to = i64::from(to);
+        let mut i = i64::from(from);
+
+        while (step > 0 && i <= to) || (step < 0 && i >= to) {
+          let value = match enum_type {
+            Some((ref name, ref members)) => {
+              Value::EnumV(name.to_string(), i as usize, members[i as usize].to_string())
+            }
+            None => Value::IntV(i as i32),
+          };
+
+          self.assign(variable, value);
+
+          match self.execute_block(run)? {
+            ControlFlow::Break => break,
+            ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+            _ => {}
+          }
+
+          i += step;
         }
       }
+      Statement::If {
+        ref condition,
+        ref then_branch,
+        ref else_branch,
+      } => {
+        let branch = match self.evaluate_expression(condition)? {
+          Value::BoolV(true) => then_branch,
+          Value::BoolV(false) => else_branch,
+          _ => panic!("Type checker will prevent this."),
+        };
+
+        // Break and continue propagate to the enclosing loop.
+        return self.execute_block(branch);
+      }
+      Statement::Case {
+        ref scrutinee,
+        ref arms,
+        ref default,
+      } => {
+        let value = self.evaluate_expression(scrutinee)?;
+
+        let arm = arms.iter().find(|arm| {
+          arm
+            .labels
+            .iter()
+            .any(|label| Value::from(label.clone()) == value)
+        });
+
+        let body = match arm {
+          Some(arm) => &arm.body,
+          None => default,
+        };
+
+        // Break and continue propagate to the enclosing loop.
+        return self.execute_block(body);
+      }
+      Statement::While {
+        ref condition,
+        ref run,
+      } => while self.evaluate_expression(condition)? == Value::BoolV(true) {
+        match self.execute_block(run)? {
+          ControlFlow::Break => break,
+          ControlFlow::Return(value) => return Ok(ControlFlow::Return(value)),
+          _ => {}
+        }
+      },
+      Statement::Break => return Ok(ControlFlow::Break),
+      Statement::Continue => return Ok(ControlFlow::Continue),
+      Statement::Function(ref function) => {
+        self.functions.insert(function.name.to_string(), function);
+      }
+      Statement::TypeDefinition {
+        ref name,
+        ref definition,
+      } => {
+        self
+          .user_types
+          .insert(name.to_string(), definition.clone());
+
+        // Enum members are global constants.
+        if let UserType::Enum(ref members) = *definition {
+          for (index, member) in members.iter().enumerate() {
+            let value = Value::EnumV(name.to_string(), index, member.to_string());
+            self.declare(member, TypeName::NamedType(name.to_string()), value);
+          }
+        }
+      }
+      Statement::Call(ref name, ref arguments) => {
+        self.call(name, arguments)?;
+      }
+      Statement::Return(ref value) => {
+        let value = match *value {
+          Some(ref expr) => Some(self.evaluate_expression(expr)?),
+          None => None,
+        };
+        return Ok(ControlFlow::Return(value));
+      }
     }
+
+    Ok(ControlFlow::Next)
   }
 
-  pub fn execute(&mut self, program: Program) {
-    for statement in program {
-      self.execute_statement(statement);
+  /// Executes a list of statements, stopping early if a statement breaks out of the normal flow.
+  fn execute_statements(
+    &mut self,
+    statements: &'a [StatementWithCtx],
+  ) -> RuntimeResult<ControlFlow> {
+    // The enclosing statement continues to report errors using its own position afterwards.
+    let position = self.position.clone();
+    let mut flow = ControlFlow::Next;
+
+    for statement in statements {
+      flow = self.execute_statement(statement)?;
+
+      if flow != ControlFlow::Next {
+        break;
+      }
     }
+
+    self.position = position;
+    Ok(flow)
+  }
+
+  /// Executes a list of statements in a new scope.
+  fn execute_block(&mut self, statements: &'a [StatementWithCtx]) -> RuntimeResult<ControlFlow> {
+    self.current_frame().push(HashMap::new());
+    let flow = self.execute_statements(statements)?;
+    self.current_frame().pop();
+    Ok(flow)
+  }
+
+  pub fn execute(&mut self, program: Program<'a>) -> Result<(), RuntimeErrorWithCtx> {
+    // The type checker prevents break, continue and return outside their enclosing constructs.
+    self.execute_statements(program).map(|_| ())
   }
 }
@@ -4,13 +4,12 @@ use common::types::TypeName::*;
 use parsing::ast::*;
 use parsing::ast::Expression::*;
 
-pub fn expr_of_type(t: TypeName) -> Expression {
-  match t {
-    BoolType => BinaryOp(
-      BinaryOperator::Equal,
-      Box::new((expr_of_type(IntType), expr_of_type(IntType))),
-    ),
+pub fn expr_of_type(t: &TypeName) -> Expression {
+  match *t {
+    BoolType => Literal(BoolLiteral(false)),
     IntType => Literal(IntLiteral(0)),
+    RealType => Literal(RealLiteral(0.0)),
     StringType => Literal(StringLiteral("".to_string())),
+    ArrayType(..) | NamedType(_) => panic!("Only primitive types have literal expressions."),
   }
 }
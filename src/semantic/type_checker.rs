@@ -1,6 +1,11 @@
 use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
 
-use common::errors::ErrorWithReason;
+use common::builtins::Builtins;
+use common::configuration::ShadowingPolicy;
+use common::errors::{ErrorWithContext, ErrorWithReason};
+use common::logger::Logger;
 use common::types::*;
 use parsing::ast::*;
 
@@ -23,20 +28,38 @@ pub enum TypeError {
   ReadArgumentError(TypeName),
   AssertArgumentError(TypeName),
   AssignToImmutable(String),
+  BreakOutsideLoop,
+  ContinueOutsideLoop,
+  NestedDeclaration(String),
+  ArgumentCountMismatch {
+    name: String,
+    expected: usize,
+    was: usize,
+  },
+  NoReturnValue(String),
+  ReturnOutsideFunction,
+  InvalidReturn(String),
+  MissingReturn(String),
+  InvalidIndex(TypeName),
+  UndeclaredType(String),
+  UnknownField {
+    type_of: TypeName,
+    field: String,
+  },
+  ShadowedIdentifier(String),
+  InvalidConversion {
+    from: TypeName,
+    to: TypeName,
+  },
+  InterpolationArgumentError(TypeName),
+  EmptyEnum(String),
+  CaseArgumentError(TypeName),
+  DuplicateCaseLabel(LiteralValue),
 }
 
 impl ErrorWithReason for TypeError {
   fn get_reason(&self) -> Option<String> {
     use TypeError::*;
-    use common::types::TypeName::*;
-
-    fn format_type_name(type_name: TypeName) -> &'static str {
-      match type_name {
-        IntType => "int",
-        StringType => "string",
-        BoolType => "bool",
-      }
-    }
 
     match self {
       RedeclaredIdentifier(name) => Some(format!("Identifier {} was redeclared.", name)),
@@ -49,30 +72,242 @@ impl ErrorWithReason for TypeError {
         new_type,
       } => Some(format!(
         "Tried to assign <{}> to \"{}\", which is <{}>.",
-        format_type_name(*was),
+        was, name, new_type
+      )),
+      IncompatibleTypes { expected, was } => {
+        Some(format!("Expected <{}>, but was <{}>.", expected, was))
+      }
+      InvalidBinaryOp(op, left, right) => Some(format!(
+        "Operator {} can't be applied to <{}> and <{}>.",
+        op, left, right
+      )),
+      InvalidUnaryOp(op, type_of) => {
+        Some(format!("Operator {} can't be applied to <{}>.", op, type_of))
+      }
+      PrintArgumentError(type_of) => Some(format!(
+        "<{}> can't be printed. Only int, real, string, bool and enum values can be.",
+        type_of
+      )),
+      ReadArgumentError(type_of) => Some(format!(
+        "<{}> can't be read. Only int, real, string and bool variables can be.",
+        type_of
+      )),
+      AssertArgumentError(type_of) => {
+        Some(format!("assert expects <bool>, but was <{}>.", type_of))
+      }
+      AssignToImmutable(name) => Some(format!(
+        "{} is immutable (a constant, enum member or loop variable), so it can't be changed.",
+        name
+      )),
+      BreakOutsideLoop => Some("break can only be used inside a loop.".to_string()),
+      ContinueOutsideLoop => Some("continue can only be used inside a loop.".to_string()),
+      NestedDeclaration(name) => Some(format!(
+        "{} must be declared at the top level of the program.",
+        name
+      )),
+      ArgumentCountMismatch {
         name,
-        format_type_name(*new_type)
+        expected,
+        was,
+      } => Some(format!(
+        "{} expects {} argument(s), but was called with {}.",
+        name, expected, was
+      )),
+      NoReturnValue(name) => Some(format!(
+        "Procedure {} doesn't return a value, so it can't be used in an expression.",
+        name
+      )),
+      ReturnOutsideFunction => Some("return can only be used inside a subroutine.".to_string()),
+      InvalidReturn(name) => Some(format!(
+        "Functions must return a value and procedures must not (in {}).",
+        name
+      )),
+      MissingReturn(name) => Some(format!(
+        "Function {} doesn't return a value on every path.",
+        name
+      )),
+      InvalidIndex(type_name) => Some(format!("<{}> can't be indexed.", type_name)),
+      UndeclaredType(name) => Some(format!("Type {} was used before declaration.", name)),
+      UnknownField { type_of, field } => {
+        Some(format!("<{}> doesn't have a field called {}.", type_of, field))
+      }
+      InterpolationArgumentError(type_of) => {
+        Some(format!("<{}> can't be interpolated into a string.", type_of))
+      }
+      EmptyEnum(name) => Some(format!("Enum {} must have at least one member.", name)),
+      CaseArgumentError(type_of) => Some(format!(
+        "Case statements can only match <int>, <string> or <bool>, not <{}>.",
+        type_of
+      )),
+      DuplicateCaseLabel(label) => Some(format!(
+        "Case label {} is used more than once.",
+        Value::from(label.clone())
+      )),
+      InvalidConversion { from, to } => Some(format!("Can't convert <{}> to <{}>.", from, to)),
+      ShadowedIdentifier(name) => Some(format!(
+        "Identifier {} shadows a variable declared in an outer scope.",
+        name
       )),
-      _ => None,
     }
   }
 }
 
+/// A type error, with the source position of the statement which caused it.
+#[derive(Debug)]
+pub struct TypeErrorWithCtx(pub TypeError, pub Range<usize>);
+
+impl ErrorWithContext for TypeErrorWithCtx {
+  fn get_offset(&self) -> usize {
+    self.1.start
+  }
+}
+
+impl ErrorWithReason for TypeErrorWithCtx {
+  fn get_reason(&self) -> Option<String> {
+    self.0.get_reason()
+  }
+}
+
 struct Symbol {
   type_of: TypeName,
   is_mutable: bool,
+  // The value of a constant with a literal initialiser.
+  value: Option<LiteralValue>,
+}
+
+struct FunctionSignature {
+  parameters: Vec<TypeName>,
+  return_type: Option<TypeName>,
 }
 
+type Scope = HashMap<String, Symbol>;
+
 struct TypeCheckingContext {
-  symbols: HashMap<String, Symbol>,
+  // The innermost scope is last. The first scope contains global variables.
+  scopes: Vec<Scope>,
+  functions: HashMap<String, FunctionSignature>,
+  builtins: Builtins,
+  user_types: UserTypes,
+  current_function: Option<String>,
+  // The number of loops enclosing the current statement.
+  loop_depth: usize,
+  // Source position of the statement being checked, used for error reporting.
+  position: Range<usize>,
+  shadowing: ShadowingPolicy,
+  logger: Rc<dyn Logger>,
+}
+
+// Returns true if executing the statements is guaranteed to end in a return statement.
+fn always_returns(statements: &[StatementWithCtx]) -> bool {
+  statements.iter().any(|statement| match statement.statement {
+    Statement::Return(_) => true,
+    Statement::If {
+      ref then_branch,
+      ref else_branch,
+      ..
+    } => always_returns(then_branch) && always_returns(else_branch),
+    Statement::Case {
+      ref arms,
+      ref default,
+      ..
+    } => arms.iter().all(|arm| always_returns(&arm.body)) && always_returns(default),
+    _ => false,
+  })
 }
 
 impl TypeCheckingContext {
+  fn new(shadowing: ShadowingPolicy, logger: Rc<dyn Logger>) -> TypeCheckingContext {
+    TypeCheckingContext {
+      scopes: vec![HashMap::new()],
+      functions: HashMap::new(),
+      builtins: Builtins::default(),
+      user_types: HashMap::new(),
+      current_function: None,
+      loop_depth: 0,
+      position: 0..0,
+      shadowing,
+      logger,
+    }
+  }
+
+  // Symbols in inner scopes shadow symbols in outer scopes.
+  fn get_symbol(&self, name: &str) -> Option<&Symbol> {
+    self.scopes.iter().rev().filter_map(|scope| scope.get(name)).next()
+  }
+
+  fn get_symbol_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+    self
+      .scopes
+      .iter_mut()
+      .rev()
+      .filter_map(|scope| scope.get_mut(name))
+      .next()
+  }
+
+  // Adds a symbol to the innermost scope.
+  fn declare_symbol(&mut self, name: &str, symbol: Symbol) -> Result<(), TypeError> {
+    let is_redeclaration = self
+      .scopes
+      .last()
+      .expect("There is always at least one scope.")
+      .contains_key(name);
+
+    if is_redeclaration || self.functions.contains_key(name) {
+      return Err(TypeError::RedeclaredIdentifier(name.to_string()));
+    }
+
+    if self.get_symbol(name).is_some() {
+      let error = TypeError::ShadowedIdentifier(name.to_string());
+
+      match self.shadowing {
+        ShadowingPolicy::Error => return Err(error),
+        ShadowingPolicy::Warn => self
+          .logger
+          .write_line(&format!("WARNING: {}", error.get_reason().unwrap())),
+      }
+    }
+
+    self
+      .scopes
+      .last_mut()
+      .expect("There is always at least one scope.")
+      .insert(name.to_string(), symbol);
+
+    Ok(())
+  }
+
+  // Type checks statements in a new scope.
+  fn type_check_block(&mut self, statements: &mut [StatementWithCtx]) -> Result<(), TypeError> {
+    self.scopes.push(HashMap::new());
+    self.type_check_statements(statements)?;
+    self.scopes.pop();
+
+    Ok(())
+  }
+
+  fn type_check_statements(
+    &mut self,
+    statements: &mut [StatementWithCtx],
+  ) -> Result<(), TypeError> {
+    // The enclosing statement continues to report errors using its own position afterwards.
+    let position = self.position.clone();
+
+    for statement in statements {
+      self.position = statement.source_position.clone();
+      self.type_check_statement(&mut statement.statement)?;
+    }
+
+    self.position = position;
+    Ok(())
+  }
+
   fn get_literal_type(&self, literal: &LiteralValue) -> TypeName {
     use self::LiteralValue::*;
     match *literal {
       StringLiteral(_) => TypeName::StringType,
       IntLiteral(_) => TypeName::IntType,
+      RealLiteral(_) => TypeName::RealType,
+      BoolLiteral(_) => TypeName::BoolType,
     }
   }
 
@@ -86,8 +321,8 @@ impl TypeCheckingContext {
   }
 
   fn evaluate_variable_type(&self, variable: &str) -> Result<TypeName, TypeError> {
-    if let Some(symbol) = self.symbols.get(variable) {
-      Ok(symbol.type_of)
+    if let Some(symbol) = self.get_symbol(variable) {
+      Ok(symbol.type_of.clone())
     } else {
       Err(TypeError::UndeclaredIdentifier(variable.to_string()))
     }
@@ -103,33 +338,190 @@ impl TypeCheckingContext {
     match *expression {
       Literal(ref literal) => Ok(self.get_literal_type(literal)),
       Variable(ref variable) => self.evaluate_variable_type(variable),
+      Call(ref name, ref arguments) => self
+        .evaluate_call_type(name, arguments)?
+        .ok_or_else(|| NoReturnValue(name.to_string())),
       BinaryOp(ref op, ref params) => {
         let (left, right) = self.evaluate_binary_expression_type(params)?;
         match (*op, left, right) {
           (Add, IntType, IntType)
           | (Sub, IntType, IntType)
           | (Mul, IntType, IntType)
-          | (Div, IntType, IntType) => Ok(IntType),
+          | (Div, IntType, IntType)
+          | (Rem, IntType, IntType) => Ok(IntType),
+
+          // Ints and reals can't be mixed without an explicit conversion.
+          (Add, RealType, RealType)
+          | (Sub, RealType, RealType)
+          | (Mul, RealType, RealType)
+          | (Div, RealType, RealType) => Ok(RealType),
 
           (Add, StringType, StringType) => Ok(StringType),
 
-          (Equal, x, y) if x == y => Ok(BoolType),
-          (LessThan, x, y) if x == y => Ok(BoolType),
-          (And, BoolType, BoolType) => Ok(BoolType),
+          (Equal, x, y) | (NotEqual, x, y) if x == y => Ok(BoolType),
+
+          (LessThan, x, y)
+          | (LessThanOrEqual, x, y)
+          | (GreaterThan, x, y)
+          | (GreaterThanOrEqual, x, y) if x == y && self.is_ordered(&x) => Ok(BoolType),
+
+          (And, BoolType, BoolType) | (Or, BoolType, BoolType) => Ok(BoolType),
 
           (op, left, right) => Err(InvalidBinaryOp(op, left, right)),
         }
       }
+      Index(ref params) => {
+        let (array, index) = self.evaluate_binary_expression_type(params)?;
+        Self::assert_types_equal(IntType, index)?;
+        match array {
+          // Indexing a string results in a single character string.
+          StringType => Ok(StringType),
+          array => Self::get_element_type(array),
+        }
+      }
+      Slice(ref params) => {
+        let (ref target, ref start, ref end) = **params;
+        Self::assert_types_equal(IntType, self.evaluate_expression_type(start)?)?;
+        Self::assert_types_equal(IntType, self.evaluate_expression_type(end)?)?;
+        match self.evaluate_expression_type(target)? {
+          StringType => Ok(StringType),
+          other => Err(InvalidIndex(other)),
+        }
+      }
+      Field(ref record, ref field) => {
+        let record = self.evaluate_expression_type(record)?;
+        self.get_field_type(record, field)
+      }
+      Interpolation(ref parts) => {
+        for part in parts {
+          if let InterpolationPart::Value(ref value) = *part {
+            match self.evaluate_expression_type(value)? {
+              IntType | RealType | StringType | BoolType => {}
+              ref enum_type if self.is_enum(enum_type) => {}
+              other => return Err(InterpolationArgumentError(other)),
+            }
+          }
+        }
+
+        Ok(StringType)
+      }
+      Convert(ref to, ref inner) => match (to, self.evaluate_expression_type(inner)?) {
+        (&IntType, IntType) | (&IntType, RealType) => Ok(IntType),
+        (&RealType, IntType) | (&RealType, RealType) => Ok(RealType),
+        (to, from) => Err(InvalidConversion {
+          from,
+          to: to.clone(),
+        }),
+      },
       UnaryOp(ref op, ref param) => {
         let inner = self.evaluate_expression_type(param)?;
         match (*op, inner) {
           (Not, BoolType) => Ok(BoolType),
+          (Negate, IntType) => Ok(IntType),
+          (Negate, RealType) => Ok(RealType),
           (op, inner) => Err(InvalidUnaryOp(op, inner)),
         }
       }
     }
   }
 
+  fn get_element_type(array: TypeName) -> Result<TypeName, TypeError> {
+    match array {
+      TypeName::ArrayType(element, _) => Ok(*element),
+      other => Err(TypeError::InvalidIndex(other)),
+    }
+  }
+
+  fn get_field_type(&self, record: TypeName, field: &str) -> Result<TypeName, TypeError> {
+    let field_type = match record {
+      TypeName::NamedType(ref name) => match self.user_types[name] {
+        UserType::Record(ref fields) => fields
+          .iter()
+          .find(|(field_name, _)| field_name == field)
+          .map(|(_, type_of)| type_of.clone()),
+        UserType::Enum(_) => None,
+      },
+      _ => None,
+    };
+
+    field_type.ok_or_else(|| TypeError::UnknownField {
+      type_of: record,
+      field: field.to_string(),
+    })
+  }
+
+  fn evaluate_lvalue_type(&self, target: &LValue) -> Result<TypeName, TypeError> {
+    match *target {
+      LValue::Variable(ref name) => self.evaluate_variable_type(name),
+      LValue::Index(ref inner, ref index) => {
+        let array = self.evaluate_lvalue_type(inner)?;
+        Self::assert_types_equal(TypeName::IntType, self.evaluate_expression_type(index)?)?;
+        Self::get_element_type(array)
+      }
+      LValue::Field(ref inner, ref field) => {
+        let record = self.evaluate_lvalue_type(inner)?;
+        self.get_field_type(record, field)
+      }
+    }
+  }
+
+  fn is_enum(&self, type_name: &TypeName) -> bool {
+    if let TypeName::NamedType(ref name) = *type_name {
+      if let Some(&UserType::Enum(_)) = self.user_types.get(name) {
+        return true;
+      }
+    }
+
+    false
+  }
+
+  // Arrays and records have no meaningful order, so they can only be compared for equality.
+  fn is_ordered(&self, type_name: &TypeName) -> bool {
+    match *type_name {
+      TypeName::IntType | TypeName::RealType | TypeName::StringType | TypeName::BoolType => true,
+      ref other => self.is_enum(other),
+    }
+  }
+
+  // Makes sure all user-defined types referred to by the type have been declared.
+  fn assert_type_exists(&self, type_name: &TypeName) -> Result<(), TypeError> {
+    match *type_name {
+      TypeName::ArrayType(ref element, _) => self.assert_type_exists(element),
+      TypeName::NamedType(ref name) if !self.user_types.contains_key(name) => {
+        Err(TypeError::UndeclaredType(name.to_string()))
+      }
+      _ => Ok(()),
+    }
+  }
+
+  // Returns the return type of the called subroutine, or None for procedures.
+  fn evaluate_call_type(
+    &self,
+    name: &str,
+    arguments: &[Expression],
+  ) -> Result<Option<TypeName>, TypeError> {
+    // Built-in functions always return a value.
+    let (parameters, return_type) = match (self.functions.get(name), self.builtins.get(name)) {
+      (Some(signature), _) => (&signature.parameters, signature.return_type.clone()),
+      (None, Some(builtin)) => (&builtin.parameters, Some(builtin.return_type.clone())),
+      (None, None) => return Err(TypeError::UndeclaredIdentifier(name.to_string())),
+    };
+
+    if parameters.len() != arguments.len() {
+      return Err(TypeError::ArgumentCountMismatch {
+        name: name.to_string(),
+        expected: parameters.len(),
+        was: arguments.len(),
+      });
+    }
+
+    for (parameter, argument) in parameters.iter().zip(arguments) {
+      Self::assert_types_equal(parameter.clone(), self.evaluate_expression_type(argument)?)?;
+    }
+
+    Ok(return_type)
+  }
+
   fn assert_types_equal(expected: TypeName, is: TypeName) -> Result<(), TypeError> {
     if expected != is {
       Err(TypeError::IncompatibleTypes { expected, was: is })
@@ -140,16 +532,14 @@ impl TypeCheckingContext {
 
   fn set_variable_mutability(&mut self, name: &str, is_mutable: bool) {
     let symbol = self
-      .symbols
-      .get_mut(name)
+      .get_symbol_mut(name)
       .expect("Symbol should always be defined at this point.");
     symbol.is_mutable = is_mutable;
   }
 
   fn assert_mutable(&self, name: &str) -> Result<(), TypeError> {
     let symbol = self
-      .symbols
-      .get(name)
+      .get_symbol(name)
       .expect("Symbol should always be defined at this point");
     if !symbol.is_mutable {
       Err(TypeError::AssignToImmutable(name.to_string()))
@@ -158,53 +548,202 @@ impl TypeCheckingContext {
     }
   }
 
-  fn type_check_statement(&mut self, statement: &Statement) -> Result<(), TypeError> {
+  // Replaces references to constants with literal values with the values themselves.
+  fn fold_constants(&self, expression: &mut Expression) {
+    use self::Expression::*;
+
+    let value = match *expression {
+      Variable(ref name) => self.get_symbol(name).and_then(|symbol| symbol.value.clone()),
+      Literal(_) => None,
+      Call(_, ref mut arguments) => {
+        for argument in arguments {
+          self.fold_constants(argument);
+        }
+        None
+      }
+      Index(ref mut params) | BinaryOp(_, ref mut params) => {
+        self.fold_constants(&mut params.0);
+        self.fold_constants(&mut params.1);
+        None
+      }
+      Slice(ref mut params) => {
+        self.fold_constants(&mut params.0);
+        self.fold_constants(&mut params.1);
+        self.fold_constants(&mut params.2);
+        None
+      }
+      Field(ref mut inner, _) | UnaryOp(_, ref mut inner) | Convert(_, ref mut inner) => {
+        self.fold_constants(inner);
+        None
+      }
+      Interpolation(ref mut parts) => {
+        for part in parts {
+          if let InterpolationPart::Value(ref mut value) = *part {
+            self.fold_constants(value);
+          }
+        }
+        None
+      }
+    };
+
+    if let Some(value) = value {
+      *expression = Literal(value);
+    }
+  }
+
+  fn fold_lvalue_constants(&self, target: &mut LValue) {
+    match *target {
+      LValue::Variable(_) => {}
+      LValue::Index(ref mut inner, ref mut index) => {
+        self.fold_lvalue_constants(inner);
+        self.fold_constants(index);
+      }
+      LValue::Field(ref mut inner, _) => self.fold_lvalue_constants(inner),
+    }
+  }
+
+  // Folds the expressions of a statement, but not the statements nested in it. They are folded
+  // when they are checked, because they may declare constants of their own.
+  fn fold_statement_constants(&self, statement: &mut Statement) {
+    match *statement {
+      Statement::Declare {
+        initial: Some(ref mut expression),
+        ..
+      }
+      | Statement::Assert(ref mut expression)
+      | Statement::Return(Some(ref mut expression))
+      | Statement::If {
+        condition: ref mut expression,
+        ..
+      }
+      | Statement::While {
+        condition: ref mut expression,
+        ..
+      }
+      | Statement::Case {
+        scrutinee: ref mut expression,
+        ..
+      } => self.fold_constants(expression),
+      Statement::Print {
+        ref mut arguments,
+        ..
+      } => {
+        for argument in arguments {
+          self.fold_constants(&mut argument.value);
+          if let Some(ref mut width) = argument.width {
+            self.fold_constants(width);
+          }
+        }
+      }
+      Statement::Assign(ref mut target, ref mut value) => {
+        self.fold_lvalue_constants(target);
+        self.fold_constants(value);
+      }
+      Statement::For {
+        ref mut from,
+        ref mut to,
+        ref mut step,
+        ..
+      } => {
+        self.fold_constants(from);
+        self.fold_constants(to);
+        if let Some(ref mut step) = *step {
+          self.fold_constants(step);
+        }
+      }
+      Statement::Call(_, ref mut arguments) => {
+        for argument in arguments {
+          self.fold_constants(argument);
+        }
+      }
+      _ => {}
+    }
+  }
+
+  fn type_check_statement(&mut self, statement: &mut Statement) -> Result<(), TypeError> {
+    self.fold_statement_constants(statement);
+
     match *statement {
       Statement::Declare {
         ref name,
-        ref type_of,
+        ref mut type_of,
         ref initial,
+        is_constant,
+        ..
       } => {
-        // If the variable already exists in the symbol table, report error.
-        if self.symbols.get(name).is_some() {
-          return Err(TypeError::RedeclaredIdentifier(name.to_string()));
+        if let Some(ref type_of) = *type_of {
+          self.assert_type_exists(type_of)?;
         }
 
+        let initial_value_type = match *initial {
+          Some(ref initial_value) => Some(self.evaluate_expression_type(initial_value)?),
+          None => None,
+        };
+
         // If the variable has been initialised, make sure it matches the type annotation.
-        if let Some(ref initial_value) = *initial {
-          let initial_value_type = self.evaluate_expression_type(initial_value)?;
-          Self::assert_types_equal(*type_of, initial_value_type)?;
-        }
+        // Otherwise the type is inferred from the initial value, which the parser requires.
+        let type_of = match (type_of.clone(), initial_value_type) {
+          (Some(type_of), Some(initial_value_type)) => {
+            Self::assert_types_equal(type_of.clone(), initial_value_type)?;
+            type_of
+          }
+          (Some(type_of), None) => type_of,
+          (None, Some(initial_value_type)) => {
+            *type_of = Some(initial_value_type.clone());
+            initial_value_type
+          }
+          (None, None) => panic!("Parser will prevent declarations without a type or a value."),
+        };
+
+        let value = match *initial {
+          Some(Expression::Literal(ref literal)) if is_constant => Some(literal.clone()),
+          _ => None,
+        };
 
-        // Add the symbol to the symbol table.
-        self.symbols.insert(
-          name.to_string(),
+        // Add the symbol to the symbol table. This fails if the variable already exists.
+        self.declare_symbol(
+          name,
           Symbol {
-            type_of: *type_of,
-            is_mutable: true,
+            type_of,
+            is_mutable: !is_constant,
+            value,
           },
-        );
-        Ok(())
+        )
       }
-      Statement::Assign(ref name, ref value) => {
-        let variable_type = self.evaluate_variable_type(name)?;
+      Statement::Assign(ref target, ref value) => {
+        let variable_type = self.evaluate_lvalue_type(target)?;
         let value_type = self.evaluate_expression_type(value)?;
         Self::assert_types_equal(variable_type, value_type)?;
-        self.assert_mutable(name)
+        self.assert_mutable(target.get_name())
       }
-      Statement::Print(ref expr) => {
-        // Only strings and ints can be printed.
-        match self.evaluate_expression_type(expr)? {
-          TypeName::IntType | TypeName::StringType => Ok(()),
-          TypeName::BoolType => Err(TypeError::PrintArgumentError(TypeName::BoolType)),
+      Statement::Print { ref arguments, .. } => {
+        for argument in arguments {
+          // Only strings, numbers, bools and enums (by member name) can be printed.
+          match self.evaluate_expression_type(&argument.value)? {
+            TypeName::IntType | TypeName::RealType | TypeName::StringType | TypeName::BoolType => {}
+            ref enum_type if self.is_enum(enum_type) => {}
+            other => return Err(TypeError::PrintArgumentError(other)),
+          }
+
+          if let Some(ref width) = argument.width {
+            Self::assert_types_equal(TypeName::IntType, self.evaluate_expression_type(width)?)?;
+          }
         }
+
+        Ok(())
       }
-      Statement::Read(ref name) => {
-        // Make sure the variable exists, and is either an int or string.
-        match self.evaluate_variable_type(name)? {
-          TypeName::IntType | TypeName::StringType => Ok(()),
-          TypeName::BoolType => Err(TypeError::ReadArgumentError(TypeName::BoolType)),
+      Statement::Read(ref names) => {
+        // Make sure the variables exist, and are numbers, strings or bools.
+        for name in names {
+          match self.evaluate_variable_type(name)? {
+            TypeName::IntType | TypeName::RealType | TypeName::StringType | TypeName::BoolType => {
+              self.assert_mutable(name)?
+            }
+            other => return Err(TypeError::ReadArgumentError(other)),
+          }
         }
+
+        Ok(())
       }
       Statement::Assert(ref expr) => match self.evaluate_expression_type(expr)? {
         TypeName::BoolType => Ok(()),
@@ -214,36 +753,243 @@ impl TypeCheckingContext {
         ref variable,
         ref from,
         ref to,
-        ref run,
+        ref step,
+        ref mut run,
+        ..
       } => {
-        // Loop variable must be a mutable integer
-        Self::assert_types_equal(TypeName::IntType, self.evaluate_variable_type(variable)?)?;
+        // Loop variable must be a mutable integer or enum, and the bounds must be of the same type.
+        let variable_type = match self.evaluate_variable_type(variable)? {
+          ref enum_type if self.is_enum(enum_type) => enum_type.clone(),
+          other => {
+            Self::assert_types_equal(TypeName::IntType, other)?;
+            TypeName::IntType
+          }
+        };
         self.assert_mutable(variable)?;
 
-        Self::assert_types_equal(TypeName::IntType, self.evaluate_expression_type(from)?)?;
-        Self::assert_types_equal(TypeName::IntType, self.evaluate_expression_type(to)?)?;
+        Self::assert_types_equal(variable_type.clone(), self.evaluate_expression_type(from)?)?;
+        Self::assert_types_equal(variable_type, self.evaluate_expression_type(to)?)?;
+
+        // Enum ranges are stepped through by member index.
+        if let Some(ref step) = *step {
+          Self::assert_types_equal(TypeName::IntType, self.evaluate_expression_type(step)?)?;
+        }
 
         self.set_variable_mutability(variable, false);
 
-        for statement in run {
-          self.type_check_statement(&statement.statement)?;
-        }
+        self.type_check_loop_body(run)?;
 
         self.set_variable_mutability(variable, true);
 
         Ok(())
       }
+      Statement::If {
+        ref condition,
+        ref mut then_branch,
+        ref mut else_branch,
+      } => {
+        Self::assert_types_equal(TypeName::BoolType, self.evaluate_expression_type(condition)?)?;
+
+        self.type_check_block(then_branch)?;
+        self.type_check_block(else_branch)
+      }
+      Statement::Case {
+        ref scrutinee,
+        ref mut arms,
+        ref mut default,
+      } => {
+        let scrutinee_type = match self.evaluate_expression_type(scrutinee)? {
+          TypeName::IntType => TypeName::IntType,
+          TypeName::StringType => TypeName::StringType,
+          TypeName::BoolType => TypeName::BoolType,
+          other => return Err(TypeError::CaseArgumentError(other)),
+        };
+
+        let mut labels: Vec<&LiteralValue> = Vec::new();
+
+        for arm in arms.iter_mut() {
+          for label in &arm.labels {
+            Self::assert_types_equal(scrutinee_type.clone(), self.get_literal_type(label))?;
+
+            if labels.contains(&label) {
+              return Err(TypeError::DuplicateCaseLabel(label.clone()));
+            }
+
+            labels.push(label);
+          }
+
+          self.type_check_block(&mut arm.body)?;
+        }
+
+        self.type_check_block(default)
+      }
+      Statement::While {
+        ref condition,
+        ref mut run,
+      } => {
+        Self::assert_types_equal(TypeName::BoolType, self.evaluate_expression_type(condition)?)?;
+        self.type_check_loop_body(run)
+      }
+      Statement::Break if self.loop_depth == 0 => Err(TypeError::BreakOutsideLoop),
+      Statement::Continue if self.loop_depth == 0 => Err(TypeError::ContinueOutsideLoop),
+      Statement::Break | Statement::Continue => Ok(()),
+      Statement::Function(ref function) => Err(TypeError::NestedDeclaration(function.name.clone())),
+      Statement::TypeDefinition { ref name, .. } => {
+        Err(TypeError::NestedDeclaration(name.to_string()))
+      }
+      Statement::Call(ref name, ref arguments) => {
+        // Function return values can be discarded.
+        self.evaluate_call_type(name, arguments).map(|_| ())
+      }
+      Statement::Return(ref value) => {
+        let name = match self.current_function {
+          Some(ref name) => name.clone(),
+          None => return Err(TypeError::ReturnOutsideFunction),
+        };
+
+        match (self.functions[&name].return_type.clone(), value) {
+          (Some(return_type), Some(value)) => {
+            Self::assert_types_equal(return_type, self.evaluate_expression_type(value)?)
+          }
+          (None, None) => Ok(()),
+          _ => Err(TypeError::InvalidReturn(name)),
+        }
+      }
+    }
+  }
+
+  fn type_check_function(&mut self, function: &mut Function) -> Result<(), TypeError> {
+    let name = &function.name;
+
+    let is_declared = self.scopes[0].contains_key(name)
+      || self.functions.contains_key(name)
+      || self.builtins.contains(name);
+
+    if is_declared {
+      return Err(TypeError::RedeclaredIdentifier(name.to_string()));
+    }
+
+    for parameter in &function.parameters {
+      self.assert_type_exists(&parameter.type_of)?;
+    }
+
+    if let Some(ref return_type) = function.return_type {
+      self.assert_type_exists(return_type)?;
+    }
+
+    // The signature is registered before checking the body to allow recursion.
+    self.functions.insert(
+      name.to_string(),
+      FunctionSignature {
+        parameters: function.parameters.iter().map(|p| p.type_of.clone()).collect(),
+        return_type: function.return_type.clone(),
+      },
+    );
+
+    // Parameters and local variables share the same scope.
+    self.scopes.push(HashMap::new());
+
+    for parameter in &function.parameters {
+      let symbol = Symbol {
+        type_of: parameter.type_of.clone(),
+        is_mutable: true,
+        value: None,
+      };
+
+      self.declare_symbol(&parameter.name, symbol)?;
+    }
+
+    self.current_function = Some(name.to_string());
+
+    self.type_check_statements(&mut function.body)?;
+
+    self.scopes.pop();
+    self.current_function = None;
+
+    if function.return_type.is_some() && !always_returns(&function.body) {
+      return Err(TypeError::MissingReturn(name.to_string()));
+    }
+
+    Ok(())
+  }
+
+  fn type_check_type_definition(
+    &mut self,
+    name: &str,
+    definition: &UserType,
+  ) -> Result<(), TypeError> {
+    if self.user_types.contains_key(name) {
+      return Err(TypeError::RedeclaredIdentifier(name.to_string()));
+    }
+
+    match *definition {
+      UserType::Record(ref fields) => {
+        for (i, (field, type_of)) in fields.iter().enumerate() {
+          // A record can't contain itself, because it's only declared after its fields.
+          self.assert_type_exists(type_of)?;
+
+          if fields[..i].iter().any(|(other, _)| other == field) {
+            return Err(TypeError::RedeclaredIdentifier(field.to_string()));
+          }
+        }
+      }
+      UserType::Enum(ref members) => {
+        if members.is_empty() {
+          return Err(TypeError::EmptyEnum(name.to_string()));
+        }
+
+        // Members are global constants, so they can't reuse the names of other variables.
+        for member in members {
+          let symbol = Symbol {
+            type_of: TypeName::NamedType(name.to_string()),
+            is_mutable: false,
+            value: None,
+          };
+
+          self.declare_symbol(member, symbol)?;
+        }
+      }
     }
+
+    self
+      .user_types
+      .insert(name.to_string(), definition.clone());
+
+    Ok(())
+  }
+
+  fn type_check_loop_body(&mut self, run: &mut [StatementWithCtx]) -> Result<(), TypeError> {
+    self.loop_depth += 1;
+    self.type_check_block(run)?;
+    self.loop_depth -= 1;
+
+    Ok(())
   }
 }
 
-pub fn type_check(program: &[StatementWithCtx]) -> Result<(), TypeError> {
-  let mut context = TypeCheckingContext {
-    symbols: HashMap::new(),
-  };
+/// Type checks the program. References to constants with literal values are replaced with the
+/// values.
+pub fn type_check(
+  program: &mut [StatementWithCtx],
+  shadowing: ShadowingPolicy,
+  logger: Rc<dyn Logger>,
+) -> Result<(), TypeErrorWithCtx> {
+  let mut context = TypeCheckingContext::new(shadowing, logger);
 
   for statement in program {
-    context.type_check_statement(&statement.statement)?;
+    context.position = statement.source_position.clone();
+
+    let result = match statement.statement {
+      // Subroutines and types can only be declared at the top level.
+      Statement::Function(ref mut function) => context.type_check_function(function),
+      Statement::TypeDefinition {
+        ref name,
+        ref definition,
+      } => context.type_check_type_definition(name, definition),
+      ref mut other => context.type_check_statement(other),
+    };
+
+    result.map_err(|err| TypeErrorWithCtx(err, context.position.clone()))?;
   }
 
   Ok(())
@@ -253,20 +999,22 @@ pub fn type_check(program: &[StatementWithCtx]) -> Result<(), TypeError> {
 #[macro_use]
 mod tests {
   use super::*;
+  use common::logger::NullLogger;
   use common::types::TypeName::*;
   use parsing::ast_test_util;
   use semantic::test_util::*;
 
   fn ctx() -> TypeCheckingContext {
-    TypeCheckingContext {
-      symbols: HashMap::new(),
-    }
+    TypeCheckingContext::new(ShadowingPolicy::Warn, Rc::new(NullLogger))
   }
 
   macro_rules! type_shorthand {
     (int) => {
       TypeName::IntType
     };
+    (real) => {
+      TypeName::RealType
+    };
     (boolean) => {
       TypeName::BoolType
     };
@@ -284,10 +1032,10 @@ mod tests {
         fn $op() {
           let ctx = ctx();
 
-          for a in &[IntType, StringType, BoolType] {
-            for b in &[IntType, StringType, BoolType] {
+          for a in &[IntType, RealType, StringType, BoolType] {
+            for b in &[IntType, RealType, StringType, BoolType] {
               let result = ctx.evaluate_expression_type(
-                &ast_test_util::$op(expr_of_type(*a), expr_of_type(*b))
+                &ast_test_util::$op(expr_of_type(a), expr_of_type(b))
               );
               $(
                 if *a == type_shorthand!($a_ok) && *b == type_shorthand!($b_ok) {
@@ -310,29 +1058,110 @@ mod tests {
   operator_tests! {
     add {
       (int, int) -> int,
+      (real, real) -> real,
       (string, string) -> string
     }
     sub {
-      (int, int) -> int
+      (int, int) -> int,
+      (real, real) -> real
     }
     mul {
-      (int, int) -> int
+      (int, int) -> int,
+      (real, real) -> real
     }
     div {
+      (int, int) -> int,
+      (real, real) -> real
+    }
+    rem {
       (int, int) -> int
     }
     eq {
       (int, int) -> boolean,
+      (real, real) -> boolean,
+      (string, string) -> boolean,
+      (boolean, boolean) -> boolean
+    }
+    neq {
+      (int, int) -> boolean,
+      (real, real) -> boolean,
       (string, string) -> boolean,
       (boolean, boolean) -> boolean
     }
     lt {
       (int, int) -> boolean,
+      (real, real) -> boolean,
+      (string, string) -> boolean,
+      (boolean, boolean) -> boolean
+    }
+    lte {
+      (int, int) -> boolean,
+      (real, real) -> boolean,
+      (string, string) -> boolean,
+      (boolean, boolean) -> boolean
+    }
+    gt {
+      (int, int) -> boolean,
+      (real, real) -> boolean,
+      (string, string) -> boolean,
+      (boolean, boolean) -> boolean
+    }
+    gte {
+      (int, int) -> boolean,
+      (real, real) -> boolean,
       (string, string) -> boolean,
       (boolean, boolean) -> boolean
     }
     and {
       (boolean, boolean) -> boolean
     }
+    or {
+      (boolean, boolean) -> boolean
+    }
+  }
+
+  #[test]
+  fn declaration_types_are_inferred() {
+    let mut ctx = ctx();
+    let mut declaration = Statement::Declare {
+      name: "s".to_string(),
+      type_of: None,
+      initial: Some(ast_test_util::string("text")),
+      is_constant: false,
+      doc: None,
+    };
+
+    assert_eq!(Ok(()), ctx.type_check_statement(&mut declaration));
+    assert_match!(declaration => Statement::Declare { type_of: Some(StringType), .. });
+  }
+
+  #[test]
+  fn constants_with_literal_values_are_folded() {
+    let mut ctx = ctx();
+
+    let mut constant = Statement::Declare {
+      name: "n".to_string(),
+      type_of: Some(IntType),
+      initial: Some(ast_test_util::int(3)),
+      is_constant: true,
+      doc: None,
+    };
+    let mut usage = ast_test_util::print(ast_test_util::add(
+      Expression::Variable("n".to_string()),
+      ast_test_util::int(1),
+    ));
+
+    assert_eq!(Ok(()), ctx.type_check_statement(&mut constant));
+    assert_eq!(Ok(()), ctx.type_check_statement(&mut usage));
+
+    match usage {
+      Statement::Print { ref arguments, .. } => match arguments[0].value {
+        Expression::BinaryOp(_, ref params) => {
+          assert_match!(params.0 => Expression::Literal(LiteralValue::IntLiteral(3)));
+        }
+        ref other => panic!("Expected a binary expression, was {:?}", other),
+      },
+      other => panic!("Expected a print statement, was {:?}", other),
+    }
   }
 }
@@ -0,0 +1,3 @@
+import "broken.pl";
+
+print 1;
@@ -0,0 +1,3 @@
+import "cycle_b.pl";
+
+print "a";
@@ -0,0 +1,3 @@
+import "lib/math.pl";
+
+const greeting := "squares up to " + to_string(square(limit));
@@ -0,0 +1 @@
+const limit := 4;
@@ -0,0 +1,5 @@
+import "limits.pl";
+
+function square(x : int) : int do
+  return x * x;
+end function;
@@ -0,0 +1,5 @@
+import "greeting.pl";
+import "lib/math.pl";
+
+print greeting;
+print square(limit);
@@ -0,0 +1 @@
+import "does_not_exist.pl";
@@ -0,0 +1,3 @@
+import "statement.pl";
+
+print "main";
@@ -0,0 +1,6 @@
+function twice(x : int) : int do
+  var y : int := 1;
+
+  y := "two";
+  return y * x;
+end function;
@@ -0,0 +1,3 @@
+import "type_error.pl";
+
+print twice(2);
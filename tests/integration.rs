@@ -1,11 +1,11 @@
-#![feature(slice_patterns)]
-
 extern crate miniplrs;
 
 use std::rc::Rc;
 
+use miniplrs::common::configuration::ShadowingPolicy;
 use miniplrs::common::errors::*;
 use miniplrs::common::logger::NullLogger;
+use miniplrs::diagnostics::file_context::FileContextSource;
 use miniplrs::run_script;
 use miniplrs::runtime::Io;
 
@@ -63,7 +63,14 @@ macro_rules! integration_tests {
 
         let source: &'static str = $src;
         let mut io = TestIo::new(input);
-        let result = run_script(source, &mut io, Rc::new(NullLogger), None);
+        let result = run_script(
+          source,
+          None,
+          &mut io,
+          Rc::new(NullLogger),
+          &mut FileContextSource::new(),
+          ShadowingPolicy::Warn,
+        );
 
         let result_with_sliced_errors: Result<(), &[ExecutionError]> = if let Err(ref errors) = result {
           let sliced_errors = errors.as_slice();
@@ -82,8 +89,10 @@ macro_rules! integration_tests {
 use miniplrs::ExecutionError;
 use miniplrs::common::errors::LexerError::*;
 use miniplrs::common::errors::ParserError::*;
+use miniplrs::common::errors::RuntimeError::*;
 use miniplrs::parsing::token::TokenKind::*;
 use miniplrs::semantic::type_checker::TypeError::*;
+use miniplrs::semantic::type_checker::TypeErrorWithCtx;
 
 integration_tests! {
   empty_program("") {
@@ -138,7 +147,7 @@ integration_tests! {
     a := 10;
     print a;
   "#) {
-    result Err(&[ExecutionError::TypeError(UndeclaredIdentifier(_))]),
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(UndeclaredIdentifier(_), _))]),
     input [],
     output []
   }
@@ -238,7 +247,7 @@ integration_tests! {
       i := 100;
     end for;
   "#) {
-    result Err(&[ExecutionError::TypeError(AssignToImmutable(_))]),
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
     input [],
     output []
   }
@@ -328,15 +337,2070 @@ print v;
   output ["Give a number", "The result is: ", "120"]
 }
 
-  print_uninitialised_variable(r#"
-    var a : int;
-    print a;
+  if_else_basic(r#"
+    var i : int;
+    for i in 1 .. 4 do
+      if i < 3 then
+        print "small";
+      else
+        print "big";
+      end if;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["small", "small", "big", "big"]
+  }
 
-    var b : string;
-    print b;
+  if_without_else(r#"
+    var x : int := 5;
+    if x = 5 then
+      print "five";
+    end if;
+    if x = 6 then
+      print "six";
+    end if;
   "#) {
     result Ok(_),
     input [],
-    output ["0", ""]
+    output ["five"]
+  }
+
+  if_nested(r#"
+    var x : int := 2;
+    if x < 3 then
+      if x = 1 then
+        print "one";
+      else
+        print "two";
+      end if;
+    else
+      print "many";
+    end if;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["two"]
+  }
+
+  if_condition_must_be_bool(r#"
+    if 1 then
+      print "one";
+    end if;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  while_read_until_zero(r#"
+    var n : int := 1;
+    var sum : int := 0;
+    while !(n = 0) do
+      read n;
+      sum := sum + n;
+    end while;
+    print sum;
+  "#) {
+    result Ok(_),
+    input ["3", "4", "0"],
+    output ["7"]
+  }
+
+  while_break_and_continue(r#"
+    var i : int := 0;
+    while 1 = 1 do
+      i := i + 1;
+      if i = 2 then
+        continue;
+      end if;
+      if 4 < i then
+        break;
+      end if;
+      print i;
+    end while;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1", "3", "4"]
+  }
+
+  for_break_and_continue(r#"
+    var i : int;
+    var j : int;
+    for i in 1 .. 3 do
+      for j in 1 .. 3 do
+        if j = 2 then
+          continue;
+        end if;
+        if i = 3 then
+          break;
+        end if;
+        print i * 10 + j;
+      end for;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["11", "13", "21", "23"]
+  }
+
+  break_outside_loop(r#"
+    if 1 = 1 then
+      break;
+    end if;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(BreakOutsideLoop, _))]),
+    input [],
+    output []
+  }
+
+  continue_outside_loop(r#"
+    continue;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(ContinueOutsideLoop, _))]),
+    input [],
+    output []
+  }
+
+  procedure_with_parameters(r#"
+    procedure greet(name : string, times : int) do
+      var i : int;
+      for i in 1 .. times do
+        print "Hello, " + name;
+      end for;
+    end procedure;
+
+    greet("world", 2);
+    greet("again", 1);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Hello, world", "Hello, world", "Hello, again"]
+  }
+
+  function_recursion(r#"
+    function factorial(n : int) : int do
+      if n < 2 then
+        return 1;
+      else
+        return n * factorial(n - 1);
+      end if;
+    end function;
+
+    print factorial(5);
+    print factorial(factorial(3)) + 1;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["120", "721"]
+  }
+
+  function_return_from_loop(r#"
+    function first_multiple(n : int, above : int) : int do
+      var i : int;
+      for i in above .. above + n do
+        if (i / n) * n = i then
+          return i;
+        end if;
+      end for;
+      return 0;
+    end function;
+
+    print first_multiple(7, 20);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["21"]
+  }
+
+  function_locals_and_globals(r#"
+    var counter : int := 0;
+    var x : int := 100;
+
+    procedure increment(x : int) do
+      counter := counter + x;
+    end procedure;
+
+    increment(2);
+    increment(3);
+    print counter;
+    print x;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["5", "100"]
+  }
+
+  function_wrong_argument_count(r#"
+    procedure p(a : int) do
+      print a;
+    end procedure;
+    p(1, 2);
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(
+      ArgumentCountMismatch { expected: 1, was: 2, .. },
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  function_missing_return(r#"
+    function f(a : int) : int do
+      if a < 0 then
+        return 0;
+      end if;
+    end function;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(MissingReturn(_), _))]),
+    input [],
+    output []
+  }
+
+  procedure_in_expression(r#"
+    procedure p() do
+      print "p";
+    end procedure;
+    print p();
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(NoReturnValue(_), _))]),
+    input [],
+    output []
+  }
+
+  return_outside_function(r#"
+    return;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(ReturnOutsideFunction, _))]),
+    input [],
+    output []
+  }
+
+  nested_function(r#"
+    procedure outer() do
+      procedure inner() do
+        print "inner";
+      end procedure;
+    end procedure;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(NestedDeclaration(_), _))]),
+    input [],
+    output []
+  }
+
+  array_assignment_and_indexing(r#"
+    var xs : array[5] of int;
+    var i : int;
+    for i in 0 .. 4 do
+      xs[i] := i * i;
+    end for;
+    var sum : int := 0;
+    for i in 0 .. 4 do
+      sum := sum + xs[i];
+    end for;
+    print sum;
+    print xs[xs[2]];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["30", "16"]
+  }
+
+  array_nested(r#"
+    var grid : array[2] of array[3] of string;
+    grid[1][2] := "x";
+    print grid[1][2];
+    print grid[0][2];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["x", ""]
+  }
+
+  array_value_semantics(r#"
+    var a : array[2] of int;
+    var b : array[2] of int;
+    a[0] := 1;
+    b := a;
+    b[0] := 2;
+    print a[0];
+    assert !(a = b);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1"]
+  }
+
+  array_out_of_bounds_read(r#"
+    var xs : array[3] of int;
+    print "before";
+    print xs[3];
+    print "after";
+  "#) {
+    result Err(&[
+      ExecutionError::RuntimeError(RuntimeErrorWithCtx(IndexOutOfBounds { index: 3, length: 3 }, _))
+    ]),
+    input [],
+    output ["before"]
+  }
+
+  array_out_of_bounds_write(r#"
+    var xs : array[3] of int;
+    var i : int := 0;
+    i := i - 1;
+    xs[i] := 10;
+  "#) {
+    result Err(&[
+      ExecutionError::RuntimeError(RuntimeErrorWithCtx(
+        IndexOutOfBounds { index: -1, length: 3 },
+        _
+      ))
+    ]),
+    input [],
+    output []
+  }
+
+  array_index_must_be_int(r#"
+    var xs : array[3] of int;
+    print xs["a"];
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  array_zero_length(r#"
+    var xs : array[0] of int;
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(InvalidArrayLength, _))]),
+    input [],
+    output []
+  }
+
+  record_fields(r#"
+    type Point = record
+      x : int;
+      y : int;
+    end record;
+
+    var p : Point;
+    p.x := 3;
+    p.y := p.x * 2;
+    print p.x + p.y;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["9"]
+  }
+
+  record_default_values(r#"
+    type Student = record
+      name : string;
+      scores : array[2] of int;
+      passed : bool;
+    end record;
+
+    var s : Student;
+    print s.scores[1];
+    assert !s.passed;
+    s.scores[1] := 5;
+    print s.scores[1];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["0", "5"]
+  }
+
+  record_nested_and_arrays(r#"
+    type Point = record
+      x : int;
+      y : int;
+    end record;
+
+    type Line = record
+      from : Point;
+      to : Point;
+    end record;
+
+    var lines : array[2] of Line;
+    lines[1].to.y := 42;
+    print lines[1].to.y;
+    print lines[0].to.y;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["42", "0"]
+  }
+
+  record_structural_equality(r#"
+    type Point = record
+      x : int;
+      y : int;
+    end record;
+
+    var a : Point;
+    var b : Point;
+    assert a = b;
+    a.x := 1;
+    assert !(a = b);
+    b.x := 1;
+    assert a = b;
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  record_as_parameter(r#"
+    type Point = record
+      x : int;
+      y : int;
+    end record;
+
+    function sum(p : Point) : int do
+      return p.x + p.y;
+    end function;
+
+    var p : Point;
+    p.x := 1;
+    p.y := 2;
+    print sum(p);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["3"]
+  }
+
+  record_unknown_field(r#"
+    type Point = record
+      x : int;
+    end record;
+
+    var p : Point;
+    print p.z;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(UnknownField { .. }, _))]),
+    input [],
+    output []
+  }
+
+  record_undeclared_type(r#"
+    var p : Point;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(UndeclaredType(_), _))]),
+    input [],
+    output []
+  }
+
+  record_different_types_not_equal(r#"
+    type A = record
+      x : int;
+    end record;
+    type B = record
+      x : int;
+    end record;
+
+    var a : A;
+    var b : B;
+    assert a = b;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  scope_block_variable_not_visible_outside(r#"
+    var i : int;
+    for i in 1..2 do
+      var x : int := i;
+    end for;
+    print x;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(UndeclaredIdentifier(_), _))]),
+    input [],
+    output []
+  }
+
+  scope_shadowing_inner_variable(r#"
+    var x : int := 1;
+    if x = 1 then
+      var x : string := "inner";
+      print x;
+    end if;
+    print x;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["inner", "1"]
+  }
+
+  scope_fresh_variable_each_iteration(r#"
+    var i : int;
+    for i in 1..3 do
+      var total : int;
+      total := total + i;
+      print total;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1", "2", "3"]
+  }
+
+  scope_redeclaration_in_same_block(r#"
+    var b : bool;
+    while !b do
+      var x : int;
+      var x : int;
+    end while;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(RedeclaredIdentifier(_), _))]),
+    input [],
+    output []
+  }
+
+  scope_block_locals_not_visible_in_callee(r#"
+    procedure show() do
+      print x;
+    end procedure;
+
+    var b : bool := 1 = 1;
+    if b then
+      var x : int := 3;
+      show();
+    end if;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(UndeclaredIdentifier(_), _))]),
+    input [],
+    output []
+  }
+
+  comparison_operators(r#"
+    assert 2 > 1;
+    assert 1 <= 1;
+    assert 1 <= 2;
+    assert 2 >= 2;
+    assert 3 >= 2;
+    assert 1 <> 2;
+    assert !(1 <> 1);
+    assert "b" > "a";
+    assert 1 + 1 >= 2;
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  logical_or(r#"
+    var a : bool := 1 = 1;
+    var b : bool := 1 = 2;
+    assert a | b;
+    assert !(b | b);
+    assert !(b | a & b);
+    assert b | a;
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  logical_or_requires_bools(r#"
+    print 1 | 2;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  remainder(r#"
+    print 7 % 3;
+    print 0 - 7 % 3;
+    print (0 - 7) % 3;
+    print 7 % (0 - 3);
+    print 2 + 9 % 4 * 3;
+    print 2 * 9 % 4;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1", "-1", "-1", "1", "5", "2"]
+  }
+
+  left_associative_operators(r#"
+    print 10 - 4 - 3;
+    print 24 / 4 / 2;
+    assert !!(1 = 1);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["3", "3"]
+  }
+
+  remainder_requires_ints(r#"
+    print "a" % 2;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  remainder_by_zero(r#"
+    var x : int;
+    print 10 % x;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(DivisionByZero, _))]),
+    input [],
+    output []
+  }
+
+  division_by_zero(r#"
+    var x : int;
+    print 10 / x;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(DivisionByZero, _))]),
+    input [],
+    output []
+  }
+
+  unary_minus(r#"
+    var x : int := -5;
+    print x;
+    print -x;
+    print 3 - -x;
+    print -(2 + 3) * 2;
+    print - - 4;
+    print -2147483648;
+    print -7 % 3;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["-5", "5", "-2", "-10", "4", "-2147483648", "-1"]
+  }
+
+  unary_minus_requires_int(r#"
+    print -"text";
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidUnaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  unary_minus_overflow(r#"
+    var x : int := -2147483648;
+    print -x;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  int_literal_too_large(r#"
+    print 2147483648;
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(
+      LexerError(IntegerLiteralOverflow {
+        excess: Some(1),
+        ..
+      }),
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  bool_literals(r#"
+    var a : bool := true;
+    var b : bool;
+    assert a;
+    assert !b;
+    assert b = false;
+    assert true | false;
+    b := !false & a;
+    assert b;
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  bool_literal_type_mismatch(r#"
+    var x : int := true;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  real_arithmetic(r#"
+    var a : real := 7.0;
+    var b : real := 2.0;
+    print a / b;
+    print a * b - 0.5;
+    print -a;
+    print 1e-3;
+    print 2.5e10;
+    print 0.1 + 0.2;
+    assert a > b;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["3.5", "13.5", "-7.0", "0.001", "25000000000.0", "0.30000000000000004"]
+  }
+
+  real_conversions(r#"
+    var total : int := 7;
+    var count : int := 2;
+    var average : real := real(total) / real(count);
+    print average;
+    print int(average);
+    print int(-2.9);
+    print real(3);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["3.5", "3", "-2", "3.0"]
+  }
+
+  real_default_and_read(r#"
+    var r : real;
+    print r;
+    read r;
+    print r * 2.0;
+  "#) {
+    result Ok(_),
+    input ["1.25"],
+    output ["0.0", "2.5"]
+  }
+
+  real_and_int_dont_mix(r#"
+    print 1 + 2.0;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  real_assigned_to_int(r#"
+    var x : int := 2.0;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  real_invalid_conversion(r#"
+    print real("1.0");
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidConversion { .. }, _))]),
+    input [],
+    output []
+  }
+
+  real_conversion_overflow(r#"
+    print int(1e10);
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  real_range_after_integer(r#"
+    var i : int;
+    for i in 1..2 do
+      print i;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1", "2"]
+  }
+
+  string_indexing(r#"
+    var s : string := "häst";
+    print s[0];
+    print s[1];
+    print s[length(s) - 1];
+    print length(s);
+    print length("");
+  "#) {
+    result Ok(_),
+    input [],
+    output ["h", "ä", "t", "4", "0"]
+  }
+
+  string_slicing(r#"
+    var s : string := "hello world";
+    print s[0..4];
+    print s[6..length(s) - 1];
+    print s[3..3];
+    print "[" + s[3..2] + "]";
+    print s[1 + 1..2 * 2];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["hello", "world", "l", "[]", "llo"]
+  }
+
+  postfix_access_after_any_operand(r#"
+    type Point = record x : int; y : int; end record;
+    var s : string := "hello";
+    var t : string := " world";
+    var p : Point;
+    p.y := 7;
+    function copy(p : Point) : Point do
+      return p;
+    end function;
+    print "abc"[1];
+    print (s + t)[4..6];
+    print copy(p).y;
+    print "n = ${length(s)}"[4];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["b", "o w", "7", "5"]
+  }
+
+  string_index_out_of_bounds(r#"
+    var s : string := "abc";
+    print s[3];
+  "#) {
+    result Err(&[
+      ExecutionError::RuntimeError(RuntimeErrorWithCtx(IndexOutOfBounds { index: 3, length: 3 }, _))
+    ]),
+    input [],
+    output []
+  }
+
+  string_slice_out_of_bounds(r#"
+    var s : string := "abc";
+    print s[0..2];
+    print s[1..3];
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(
+      InvalidSlice { start: 1, end: 3, length: 3 },
+      _
+    ))]),
+    input [],
+    output ["abc"]
+  }
+
+  string_slice_ending_before_its_start(r#"
+    var s : string := "abc";
+    print s[2..0];
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(
+      InvalidSlice { start: 2, end: 0, length: 3 },
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  string_characters_are_immutable(r#"
+    var s : string := "abc";
+    s[0] := "x";
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidIndex(_), _))]),
+    input [],
+    output []
+  }
+
+  string_length_requires_string(r#"
+    print length(3);
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  builtin_functions(r#"
+    print abs(-5);
+    print min(3, 7) + max(3, 7);
+    print pow(3, 4);
+    print length("abc");
+    print to_string(12) + "!";
+    print parse_int("41") + 1;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["5", "10", "81", "3", "12!", "42"]
+  }
+
+  builtin_argument_types(r#"
+    print abs("text");
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  builtin_argument_count(r#"
+    print max(1);
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(ArgumentCountMismatch { .. }, _))]),
+    input [],
+    output []
+  }
+
+  builtin_cannot_be_redeclared(r#"
+    function abs(x : int) : int do
+      return x;
+    end function;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(RedeclaredIdentifier(_), _))]),
+    input [],
+    output []
+  }
+
+  builtin_runtime_error(r#"
+    print parse_int("twelve");
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(InvalidNumber(_), _))]),
+    input [],
+    output []
+  }
+
+  builtin_names_are_valid_variables(r#"
+    var max : int := 3;
+    print max(max, 4);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["4"]
+  }
+
+  string_interpolation(r#"
+    var x : int := 41;
+    var name : string := "world";
+    print "Hello, ${name}!";
+    print "x + 1 = ${x + 1}, ${x > 40}";
+    print "${x}${x}";
+    print "nested ${"[${name[0]}]"} \${x}";
+    print "${real(x) / 2.0}";
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Hello, world!", "x + 1 = 42, true", "4141", "nested [w] ${x}", "20.5"]
+  }
+
+  string_interpolation_type_error(r#"
+    var xs : array[2] of int;
+    print "${xs}";
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InterpolationArgumentError(_), _))]),
+    input [],
+    output []
+  }
+
+  string_interpolation_invalid_expression(r#"
+    print "${1;}";
+  "#) {
+    result Err(&[ExecutionError::ParserError(
+      ErrWithCtx(UnexpectedToken { expected: EndOfFileK, .. }, _)
+    )]),
+    input [],
+    output []
+  }
+
+  constant_declarations(r#"
+    const greeting : string := "Hello";
+    const n : int := 3;
+    const limit : int := n * 2;
+    var i : int;
+    for i in n..limit do
+      print "${greeting} ${i}";
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Hello 3", "Hello 4", "Hello 5", "Hello 6"]
+  }
+
+  constant_in_function(r#"
+    const base : int := 10;
+    function scale(x : int) : int do
+      return x * base;
+    end function;
+    print scale(4);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["40"]
+  }
+
+  constant_assignment(r#"
+    const n : int := 3;
+    n := 4;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  constant_read(r#"
+    const n : int := 3;
+    read n;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input ["4"],
+    output []
+  }
+
+  constant_loop_variable(r#"
+    const i : int := 0;
+    for i in 1..2 do
+    end for;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  constant_record_field_assignment(r#"
+    type Point = record
+      x : int;
+    end record;
+    var p : Point;
+    const q : Point := p;
+    q.x := 1;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  constant_requires_initialiser(r#"
+    const n : int;
+  "#) {
+    result Err(&[
+      ExecutionError::ParserError(ErrWithCtx(UnexpectedToken { expected: AssignK, .. }, _))
+    ]),
+    input [],
+    output []
+  }
+
+  read_into_loop_variable(r#"
+    var i : int;
+    for i in 1..2 do
+      read i;
+    end for;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  inferred_declarations(r#"
+    var n := 3;
+    var s := "x" + "y";
+    var r := 1.5;
+    var b := n > 2;
+    const limit := n * 2;
+    n := n + limit;
+    print n;
+    print s;
+    print r * 2.0;
+    assert b;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["9", "xy", "3.0"]
+  }
+
+  inferred_declarations_of_compound_types(r#"
+    type Point = record
+      x : int;
+    end record;
+    var p : Point;
+    p.x := 2;
+    var q := p;
+    var xs : array[2] of int;
+    var ys := xs;
+    ys[1] := q.x;
+    print ys[1];
+  "#) {
+    result Ok(_),
+    input [],
+    output ["2"]
+  }
+
+  inferred_declaration_type_is_fixed(r#"
+    var n := 3;
+    n := "text";
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  inferred_declaration_from_procedure(r#"
+    procedure p() do
+    end procedure;
+    var x := p();
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(NoReturnValue(_), _))]),
+    input [],
+    output []
+  }
+
+  imports_must_precede_statements(r#"
+    print 1;
+    import "lib.pl";
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(UnknownStatement { first: ImportK }, _))]),
+    input [],
+    output []
+  }
+
+  enums_are_printed_by_name(r#"
+    type Status = (Pending, Passed, Failed);
+    var status : Status;
+    print status;
+    status := Failed;
+    print status;
+    print "status: ${status}";
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Pending", "Failed", "status: Failed"]
+  }
+
+  enum_comparisons(r#"
+    type Status = (Pending, Passed, Failed);
+    var status := Passed;
+    assert(status = Passed);
+    assert(status <> Failed);
+    assert(Pending < status);
+    assert(status <= Passed);
+    assert(Failed > status);
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  enum_for_loop(r#"
+    type Status = (Pending, Passed, Failed);
+    var status : Status;
+    for status in Passed..Failed do
+      print status;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Passed", "Failed"]
+  }
+
+  enum_record_field(r#"
+    type Status = (Pending, Passed, Failed);
+    type Test = record
+      name : string;
+      status : Status;
+    end record;
+    var test : Test;
+    print test.status;
+    test.status := Passed;
+    print test.status;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Pending", "Passed"]
+  }
+
+  enums_of_different_types_are_incompatible(r#"
+    type Status = (Pending, Passed, Failed);
+    type Colour = (Red, Green);
+    assert(Pending = Red);
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(_, _, _), _))]),
+    input [],
+    output []
+  }
+
+  enums_are_not_integers(r#"
+    type Status = (Pending, Passed, Failed);
+    var status : Status := 1;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  enum_members_are_constant(r#"
+    type Status = (Pending, Passed, Failed);
+    Pending := Failed;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  enum_member_redeclared(r#"
+    var Passed := 1;
+    type Status = (Pending, Passed, Failed);
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(RedeclaredIdentifier(_), _))]),
+    input [],
+    output []
+  }
+
+  empty_enum(r#"
+    type Status = ();
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(EmptyEnum(_), _))]),
+    input [],
+    output []
+  }
+
+  read_multiple_values(r#"
+    var a : int;
+    var b : real;
+    var word : string;
+    var flag : bool;
+    read a, b;
+    read word, flag;
+    print a;
+    print b;
+    print word;
+    assert(flag);
+  "#) {
+    result Ok(_),
+    input ["  12   2.5 hello", "", "true"],
+    output ["12", "2.5", "hello"]
+  }
+
+  read_values_across_lines(r#"
+    var a : int;
+    var b : int;
+    read a, b;
+    print a + b;
+  "#) {
+    result Ok(_),
+    input ["1", "2"],
+    output ["3"]
+  }
+
+  read_string_with_spaces(r#"
+    var n : int;
+    var name : string;
+    var greeting : string;
+    read n, name;
+    read greeting;
+    print name;
+    print greeting;
+    print n;
+  "#) {
+    result Ok(_),
+    input ["3   Ada Lovelace ", "  hello,  world"],
+    output ["Ada Lovelace ", "  hello,  world", "3"]
+  }
+
+  read_bool(r#"
+    var flag := true;
+    read flag;
+    assert(!flag);
+  "#) {
+    result Ok(_),
+    input ["false"],
+    output []
+  }
+
+  read_invalid_number(r#"
+    var a : int;
+    read a;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(InvalidNumber(_), _))]),
+    input ["12x"],
+    output []
+  }
+
+  read_invalid_bool(r#"
+    var flag : bool;
+    read flag;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(InvalidBool(_), _))]),
+    input ["yes"],
+    output []
+  }
+
+  read_into_constant(r#"
+    var a : int;
+    const b := 1;
+    read a, b;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(AssignToImmutable(_), _))]),
+    input [],
+    output []
+  }
+
+  for_with_step(r#"
+    var i : int;
+    for i in 1..10 step 3 do
+      print i;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1", "4", "7", "10"]
+  }
+
+  for_with_negative_step(r#"
+    var i : int;
+    for i in 10..1 step -4 do
+      print i;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["10", "6", "2"]
+  }
+
+  for_downto(r#"
+    var i : int;
+    for i in 3 downto 1 do
+      print i;
+    end for;
+    for i in 5 downto 0 step 2 do
+      print i;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["3", "2", "1", "5", "3", "1"]
+  }
+
+  for_downto_enum(r#"
+    type Status = (Pending, Passed, Failed);
+    var status : Status;
+    for status in Failed downto Pending do
+      print status;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["Failed", "Passed", "Pending"]
+  }
+
+  for_with_zero_step(r#"
+    var i : int;
+    var zero := 0;
+    for i in 1..10 step zero do
+      print i;
+    end for;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(ZeroStep, _))]),
+    input [],
+    output []
+  }
+
+  for_step_must_be_int(r#"
+    var i : int;
+    for i in 1..10 step "2" do
+      print i;
+    end for;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  for_bounds_at_int_limits(r#"
+    var i : int;
+    var count := 0;
+    for i in 2147483646..2147483647 do
+      count := count + 1;
+    end for;
+    for i in -2147483647 downto -2147483648 do
+      count := count + 1;
+    end for;
+    for i in 2147483647 downto -2147483648 step 2147483647 do
+      count := count + 1;
+    end for;
+    print count;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["7"]
+  }
+
+  case_on_int(r#"
+    var i : int;
+    for i in -1..4 do
+      case i of
+        1: print "one";
+        2, 3:
+          print "two";
+          print "or three";
+        -1: print "minus one";
+      else
+        print "other";
+      end case;
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["minus one", "other", "one", "two", "or three", "two", "or three", "other"]
+  }
+
+  case_on_string(r#"
+    var choice : string;
+    read choice;
+    case choice of
+      "add": print "adding";
+      "quit": print "bye";
+    end case;
+  "#) {
+    result Ok(_),
+    input ["quit"],
+    output ["bye"]
+  }
+
+  case_on_bool(r#"
+    case 1 < 2 of
+      false: print "no";
+      true: print "yes";
+    end case;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["yes"]
+  }
+
+  case_without_match(r#"
+    case 5 of
+      1: print "one";
+    end case;
+  "#) {
+    result Ok(_),
+    input [],
+    output []
+  }
+
+  case_break_and_return(r#"
+    function sign(x : int) : int do
+      case x of
+        0: return 0;
+      else
+        if x < 0 then
+          return -1;
+        else
+          return 1;
+        end if;
+      end case;
+    end function;
+
+    var i : int;
+    for i in -1..5 do
+      case i of
+        2: break;
+      end case;
+      print sign(i);
+    end for;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["-1", "0", "1"]
+  }
+
+  case_duplicate_label(r#"
+    case 1 of
+      1, 2: print "a";
+      2: print "b";
+    end case;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(DuplicateCaseLabel(_), _))]),
+    input [],
+    output []
+  }
+
+  case_label_type_mismatch(r#"
+    case 1 of
+      "1": print "a";
+    end case;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  case_on_real(r#"
+    case 1.5 of
+      1: print "a";
+    end case;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(CaseArgumentError(_), _))]),
+    input [],
+    output []
+  }
+
+  case_label_must_be_literal(r#"
+    var x := 1;
+    case 1 of
+      x: print "a";
+    end case;
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(InvalidCaseLabel, _)), ..]),
+    input [],
+    output []
+  }
+
+  print_multiple_values(r#"
+    print "x = ", 1, ", ", 2.5, " ", true;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["x = 1, 2.5 true"]
+  }
+
+  print_bools(r#"
+    print 1 < 2;
+    print false;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["true", "false"]
+  }
+
+  println(r#"
+    println "a";
+    println;
+    println 1, 2;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["a\n", "\n", "12\n"]
+  }
+
+  print_with_width(r#"
+    var width := 3;
+    println "name":-6, "n":4;
+    println "ab":-6, 12:4;
+    println 123456:3, "|", 7:width, "|", "é":-2, "|";
+  "#) {
+    result Ok(_),
+    input [],
+    output ["name     n\n", "ab      12\n", "123456|  7|é |\n"]
+  }
+
+  print_width_out_of_range(r#"
+    var width := 1001;
+    print 1:width;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(
+      InvalidWidth { width: 1001, max: 1000 },
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  print_width_of_min_int(r#"
+    print 1:-2147483648;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(InvalidWidth { .. }, _))]),
+    input [],
+    output []
+  }
+
+  print_width_must_be_int(r#"
+    print 1:"5";
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, _))]),
+    input [],
+    output []
+  }
+
+  print_record(r#"
+    type Point = record
+      x : int;
+    end record;
+    var p : Point;
+    print "p: ", p;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(PrintArgumentError(_), _))]),
+    input [],
+    output []
+  }
+
+  unicode_identifiers(r#"
+    var äänestäjiä := 3;
+    var hyväksytyt_ääni := 2;
+    function hylätyt(kaikki : int, hyväksytyt : int) : int do
+      return kaikki - hyväksytyt;
+    end function;
+    print hylätyt(äänestäjiä, hyväksytyt_ääni);
+  "#) {
+    result Ok(_),
+    input [],
+    output ["1"]
+  }
+
+  radix_literals(r#"
+    var mask : int := 0xFF_00;
+    print 0x1F, " ", 0b1010, " ", 0o17, " ", 1_000_000, " ", mask, " ", -0x8000_0000;
+  "#) {
+    result Ok(()),
+    input [],
+    output ["31 10 15 1000000 65280 -2147483648"]
+  }
+
+  hex_literal_too_large(r#"
+    print 0x1_0000_0000;
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(
+      LexerError(IntegerLiteralOverflow {
+        excess: Some(2147483649),
+        ..
+      }),
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  nested_block_comments(r#"
+    /* The loop is disabled:
+    for i in 1..3 do
+      print i; /* Prints the index. */
+    end for;
+    */
+    /// The number of the beast.
+    const n := 666;
+    print n / 2;
+  "#) {
+    result Ok(()),
+    input [],
+    output ["333"]
+  }
+
+  unterminated_nested_block_comment(r#"
+    /* /* */
+    print 1;
+  "#) {
+    result Err(&[
+      ExecutionError::ParserError(ErrWithCtx(LexerError(UnterminatedComment), _))
+    ]),
+    input [],
+    output []
+  }
+
+  int_division_overflow(r#"
+    print -2147483648 / -1;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  int_addition_overflow(r#"
+    print 2147483647 + 1;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  int_subtraction_overflow(r#"
+    var x : int := -2147483647;
+    print x - 2;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  int_multiplication_overflow(r#"
+    print 65536 * 32768;
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(IntegerOverflow, _))]),
+    input [],
+    output []
+  }
+
+  for_downto_negative_step(r#"
+    var i : int;
+    for i in 10 downto 1 step -3 do
+      print i;
+    end for;
+  "#) {
+    result Ok(()),
+    input [],
+    output ["10", "7", "4", "1"]
+  }
+
+  arrays_are_not_ordered(r#"
+    var a : array[2] of int;
+    var b : array[2] of int;
+    assert a < b;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  records_are_not_ordered(r#"
+    type P = record x : int; end record;
+    var a : P;
+    var b : P;
+    assert a >= b;
+  "#) {
+    result Err(&[ExecutionError::TypeError(TypeErrorWithCtx(InvalidBinaryOp(..), _))]),
+    input [],
+    output []
+  }
+
+  index_temporary_values(r#"
+    type P = record xs : array[3] of int; name : string; end record;
+    function make() : P do
+      var p : P;
+      p.xs[2] := 7;
+      p.name := "äiti";
+      return p;
+    end function;
+    var p : P := make();
+    print make().xs[2];
+    print p.name[1];
+    print make().name[3];
+    print p.xs[3];
+  "#) {
+    result Err(&[ExecutionError::RuntimeError(RuntimeErrorWithCtx(
+      IndexOutOfBounds { index: 3, length: 3 },
+      _
+    ))]),
+    input [],
+    output ["7", "i", "i"]
+  }
+
+  negative_int_literal_too_large(r#"
+    print -0x8000_0001;
+  "#) {
+    result Err(&[ExecutionError::ParserError(ErrWithCtx(
+      LexerError(IntegerLiteralOverflow {
+        is_negated: true,
+        excess: Some(1),
+        ..
+      }),
+      _
+    ))]),
+    input [],
+    output []
+  }
+
+  print_uninitialised_variable(r#"
+    var a : int;
+    print a;
+
+    var b : string;
+    print b;
+  "#) {
+    result Ok(_),
+    input [],
+    output ["0", ""]
+  }
+}
+
+#[test]
+pub fn shadowing_as_error() {
+  let source = r#"
+    var x : int := 1;
+    if x = 1 then
+      var x : int := 2;
+    end if;
+  "#;
+
+  let mut io = TestIo::new(&[]);
+  let result = run_script(
+    source,
+    None,
+    &mut io,
+    Rc::new(NullLogger),
+    &mut FileContextSource::new(),
+    ShadowingPolicy::Error,
+  );
+
+  match result {
+    Err(ref errors) => assert_match!(
+      errors.as_slice() => &[ExecutionError::TypeError(TypeErrorWithCtx(ShadowedIdentifier(_), _))]
+    ),
+    Ok(_) => panic!("Shadowing should be an error."),
+  }
+}
+
+#[test]
+pub fn shadowing_parameter_as_error() {
+  let source = r#"
+    var x : int;
+    procedure p(x : int) do
+    end procedure;
+  "#;
+
+  let mut io = TestIo::new(&[]);
+  let result = run_script(
+    source,
+    None,
+    &mut io,
+    Rc::new(NullLogger),
+    &mut FileContextSource::new(),
+    ShadowingPolicy::Error,
+  );
+
+  match result {
+    Err(ref errors) => assert_match!(
+      errors.as_slice() => &[ExecutionError::TypeError(TypeErrorWithCtx(ShadowedIdentifier(_), _))]
+    ),
+    Ok(_) => panic!("Shadowing should be an error."),
+  }
+}
+
+fn run_file(
+  path: &str,
+  io: &mut TestIo,
+  ctx: &mut FileContextSource,
+) -> Result<(), Vec<ExecutionError>> {
+  let source = std::fs::read_to_string(path).expect("Test script should be readable.");
+  run_script(
+    &source,
+    Some(path.to_string()),
+    io,
+    Rc::new(NullLogger),
+    ctx,
+    ShadowingPolicy::Warn,
+  )
+}
+
+#[test]
+pub fn imports_are_relative_to_the_importing_file() {
+  let mut io = TestIo::new(&[]);
+  let result = run_file("tests/imports/main.pl", &mut io, &mut FileContextSource::new());
+
+  assert_match!(result => Ok(()));
+  assert_eq!(io.output, ["squares up to 16", "16"]);
+}
+
+#[test]
+pub fn import_cycle() {
+  let mut io = TestIo::new(&[]);
+  let mut ctx = FileContextSource::new();
+  let result = run_file("tests/imports/cycle_a.pl", &mut io, &mut ctx);
+
+  match result {
+    Err(ref errors) => match errors.as_slice() {
+      [ExecutionError::ParserError(ErrWithCtx(ParserError::CyclicImport(path), offset))] => {
+        assert_eq!(path, "cycle_a.pl");
+        let position = ctx.decode_offset(*offset).expect("Should be a valid offset.");
+        assert_eq!(ctx.file_name(position.file_id), Some("tests/imports/cycle_b.pl"));
+        assert_eq!((position.row, position.column), (1, 1));
+      }
+      other => panic!("Expected a cyclic import, was {:?}", other),
+    },
+    Ok(_) => panic!("Import cycles should be an error."),
+  }
+  assert!(io.output.is_empty());
+}
+
+#[test]
+pub fn missing_import() {
+  let mut io = TestIo::new(&[]);
+  let result = run_file("tests/imports/missing.pl", &mut io, &mut FileContextSource::new());
+
+  match result {
+    Err(ref errors) => assert_match!(
+      errors.as_slice() => &[ExecutionError::ParserError(ErrWithCtx(
+        ParserError::UnreadableImport { .. },
+        0
+      ))]
+    ),
+    Ok(_) => panic!("Missing imports should be an error."),
+  }
+}
+
+#[test]
+pub fn errors_in_imported_files_are_located_in_them() {
+  let mut io = TestIo::new(&[]);
+  let mut ctx = FileContextSource::new();
+  let result = run_file("tests/imports/broken_main.pl", &mut io, &mut ctx);
+
+  match result {
+    Err(ref errors) => match errors.as_slice() {
+      [ExecutionError::ParserError(error)] => {
+        let position = ctx.decode_offset(error.get_offset()).expect("Should be a valid offset.");
+        assert_eq!(ctx.file_name(position.file_id), Some("tests/imports/broken.pl"));
+        assert_eq!((position.row, position.column), (2, 7));
+      }
+      other => panic!("Expected a single parser error, was {:?}", other),
+    },
+    Ok(_) => panic!("Parser errors in imported files should be reported."),
+  }
+}
+
+#[test]
+pub fn imported_files_only_declare() {
+  let mut io = TestIo::new(&[]);
+  let mut ctx = FileContextSource::new();
+  let result = run_file("tests/imports/statement_main.pl", &mut io, &mut ctx);
+
+  match result {
+    Err(ref errors) => match errors.as_slice() {
+      [ExecutionError::ParserError(ErrWithCtx(ParserError::StatementInImport, offset))] => {
+        let position = ctx.decode_offset(*offset).expect("Should be a valid offset.");
+        assert_eq!(ctx.file_name(position.file_id), Some("tests/imports/statement.pl"));
+        assert_eq!((position.row, position.column), (2, 1));
+      }
+      other => panic!("Expected a statement in an import, was {:?}", other),
+    },
+    Ok(_) => panic!("Imported files shouldn't run statements."),
+  }
+  assert!(io.output.is_empty());
+}
+
+#[test]
+pub fn type_errors_in_imported_files_are_located_in_them() {
+  let mut io = TestIo::new(&[]);
+  let mut ctx = FileContextSource::new();
+  let result = run_file("tests/imports/type_error_main.pl", &mut io, &mut ctx);
+
+  match result {
+    Err(ref errors) => match errors.as_slice() {
+      [ExecutionError::TypeError(TypeErrorWithCtx(IncompatibleTypes { .. }, position))] => {
+        let position = ctx.decode_offset(position.start).expect("Should be a valid offset.");
+        assert_eq!(ctx.file_name(position.file_id), Some("tests/imports/type_error.pl"));
+        assert_eq!((position.row, position.column), (4, 3));
+      }
+      other => panic!("Expected a single type error, was {:?}", other),
+    },
+    Ok(_) => panic!("Type errors in imported files should be reported."),
+  }
+}
+
+#[test]
+pub fn deep_recursion_is_a_runtime_error() {
+  let source = r#"
+function depth(n : int) : int do
+  if n = 0 then
+    return 0;
+  end if;
+  return depth(n - 1) + 1;
+end function;
+print depth(150);
+print depth(500);
+"#;
+
+  // Debug builds need more than the default stack of test threads to reach the limit.
+  let (result, output, position) = std::thread::Builder::new()
+    .stack_size(64 * 1024 * 1024)
+    .spawn(move || {
+      let mut io = TestIo::new(&[]);
+      let mut ctx = FileContextSource::new();
+      let result = run_script(
+        source,
+        None,
+        &mut io,
+        Rc::new(NullLogger),
+        &mut ctx,
+        ShadowingPolicy::Warn,
+      );
+
+      let position = match result {
+        Err(ref errors) => match errors.as_slice() {
+          [ExecutionError::RuntimeError(RuntimeErrorWithCtx(StackOverflow(_), position))] => {
+            ctx.decode_offset(position.start).map(|position| (position.row, position.column))
+          }
+          _ => None,
+        },
+        Ok(_) => None,
+      };
+      (format!("{:?}", result), io.output, position)
+    })
+    .expect("Should spawn the test thread.")
+    .join()
+    .expect("The interpreter should not overflow the native stack.");
+
+  assert_eq!(position, Some((6, 3)), "{}", result);
+  assert_eq!(output, ["150"]);
+}
+
+// Runs a program which fails type checking, and returns the reason of its first type error.
+fn type_error_reason(source: &str) -> String {
+  let mut io = TestIo::new(&[]);
+  let result = run_script(
+    source,
+    None,
+    &mut io,
+    Rc::new(NullLogger),
+    &mut FileContextSource::new(),
+    ShadowingPolicy::Warn,
+  );
+
+  match result {
+    Err(ref errors) => match errors.as_slice() {
+      [ExecutionError::TypeError(TypeErrorWithCtx(error, _)), ..] => {
+        error.get_reason().expect("Type errors should have a reason.")
+      }
+      other => panic!("Expected a type error, was {:?}", other),
+    },
+    Ok(_) => panic!("The program should not type check."),
+  }
+}
+
+#[test]
+pub fn constants_cant_be_read_into() {
+  let reason = type_error_reason("const c : int := 1; read c;");
+  assert!(reason.starts_with("c is immutable"), "{}", reason);
+}
+
+#[test]
+pub fn constants_cant_be_loop_variables() {
+  let reason = type_error_reason("const c : int := 1; for c in 1..2 do end for;");
+  assert!(reason.starts_with("c is immutable"), "{}", reason);
+}
+
+#[test]
+pub fn records_cant_be_printed() {
+  let reason = type_error_reason(
+    r#"
+    type P = record x : int; end record;
+    var p : P;
+    println p;
+  "#,
+  );
+  assert!(reason.starts_with("<P> can't be printed."), "{}", reason);
+}
+
+#[test]
+pub fn arrays_cant_be_printed() {
+  let reason = type_error_reason("var a : array[3] of int; print a;");
+  assert!(reason.starts_with("<array[3] of int> can't be printed."), "{}", reason);
+}
+
+#[test]
+pub fn min_int_magnitude_is_reported_as_written() {
+  for &(source, quoted) in &[
+    ("print 0x8000_0000;", "0x8000_0000"),
+    ("print 1 - 2_147_483_648;", "2_147_483_648"),
+  ] {
+    let mut io = TestIo::new(&[]);
+    let result = run_script(
+      source,
+      None,
+      &mut io,
+      Rc::new(NullLogger),
+      &mut FileContextSource::new(),
+      ShadowingPolicy::Warn,
+    );
+
+    match result {
+      Err(ref errors) => match errors.as_slice() {
+        [ExecutionError::ParserError(ErrWithCtx(error @ LexerError(_), _))] => {
+          let reason = error.get_reason().expect("Lexer errors should have a reason.");
+          assert!(
+            reason.contains(&format!("literal {} overflows int by 1", quoted)),
+            "{}",
+            reason
+          );
+        }
+        other => panic!("Expected a lexer error, was {:?}", other),
+      },
+      Ok(_) => panic!("{} should not parse.", source),
+    }
   }
 }